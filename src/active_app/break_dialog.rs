@@ -0,0 +1,572 @@
+use std::time::{Duration, Instant};
+
+use tracing::error;
+use windows::{
+    core::{HRESULT, HSTRING, PCWSTR},
+    Win32::{
+        Foundation::{HWND, LPARAM, S_FALSE, S_OK, WPARAM},
+        Graphics::Gdi::{
+            GetMonitorInfoW, MonitorFromWindow, MONITORINFO, MONITOR_DEFAULTTONEAREST,
+        },
+        System::Diagnostics::Debug::MessageBeep,
+        UI::{
+            Controls::{
+                TaskDialogIndirect, TASKDIALOGCONFIG, TASKDIALOGCONFIG_0, TASKDIALOG_BUTTON,
+                TASKDIALOG_NOTIFICATIONS, TDCBF_OK_BUTTON, TDE_CONTENT, TDE_MAIN_INSTRUCTION,
+                TDF_ALLOW_DIALOG_CANCELLATION, TDF_CALLBACK_TIMER, TDF_POSITION_RELATIVE_TO_WINDOW,
+                TDF_SHOW_PROGRESS_BAR, TDM_CLICK_BUTTON, TDM_ENABLE_BUTTON, TDM_SET_ELEMENT_TEXT,
+                TDM_SET_PROGRESS_BAR_POS, TDM_SET_PROGRESS_BAR_RANGE, TDN_BUTTON_CLICKED,
+                TDN_CREATED, TDN_TIMER, TD_INFORMATION_ICON,
+            },
+            WindowsAndMessaging::{
+                FlashWindowEx, SendMessageW, SetForegroundWindow, SetWindowPos, ShowWindow,
+                FLASHWINFO, FLASHW_ALL, FLASHW_TIMERNOFG, HWND_NOTOPMOST, HWND_TOPMOST, IDOK,
+                MB_ICONWARNING, SWP_NOMOVE, SWP_NOSIZE, SWP_SHOWWINDOW, SW_HIDE,
+            },
+        },
+    },
+};
+
+use crate::{
+    break_reminder::{format_length, EscalationLevel, Reminder, RoutineProgress},
+    config::EscalationStep,
+    routine::Routine,
+};
+
+use super::ActiveApp;
+
+/// The upper bound of the progress bar range.
+const PROGRESS_RANGE: u16 = 1000;
+
+/// The id of the snooze button, clear of the common button ids.
+const SNOOZE_ID: i32 = 100;
+
+/// The id of the button that goes back to the previous routine step.
+const PREVIOUS_STEP_ID: i32 = 101;
+
+/// The id of the button that completes the current routine step.
+const NEXT_STEP_ID: i32 = 102;
+
+/// How the user responded to the break reminder.
+pub enum BreakResponse {
+    /// The reminder was dismissed or closed itself, ending the break.
+    Ended,
+
+    /// The user asked to be reminded again later.
+    Snoozed,
+}
+
+/// State shared with the task dialog callback while the break reminder is open.
+struct BreakDialogState {
+    /// When the reminder was shown.
+    started: Instant,
+
+    /// How long the break should last.
+    length: Duration,
+
+    /// If the reminder should close itself once the break is over.
+    auto_close: bool,
+
+    /// If the reminder cannot be dismissed until the break is over.
+    enforce_length: bool,
+
+    /// The remaining seconds last shown, used to only update the text when it changes.
+    shown_seconds: Option<u64>,
+
+    /// The steps the reminder escalates through while it is left open.
+    escalation_steps: Vec<EscalationStep>,
+
+    /// How many steps the notifier has reached.
+    escalation_level: EscalationLevel,
+
+    /// How many steps have been applied to the dialog.
+    escalations_applied: usize,
+
+    /// The window that owns the dialog, covers the screen once escalated to full screen.
+    owner: HWND,
+
+    /// If the owner window has been made to cover the screen.
+    full_screen: bool,
+
+    /// The reminder text, shown above the routine step.
+    message: String,
+
+    /// The routine being walked through and its position, if the break has one.
+    routine: Option<RoutineState>,
+
+    /// Lets the notifier know the countdown has started, taken once the dialog is shown.
+    on_started: Option<Box<dyn FnOnce()>>,
+}
+
+/// The position in a routine walked through during a break.
+struct RoutineState {
+    /// The routine being walked through.
+    routine: Routine,
+
+    /// How many steps have been completed, shared with the notifier.
+    progress: RoutineProgress,
+
+    /// The index of the step being shown.
+    step: usize,
+
+    /// When the step being shown was started.
+    step_started: Instant,
+
+    /// The remaining seconds of the step last shown, used to only update the text when it
+    /// changes.
+    shown_seconds: Option<u64>,
+}
+
+impl ActiveApp {
+    /// Shows the break reminder with a countdown for the length of the break, blocks until the
+    /// reminder is closed. `on_started` is called once the countdown starts.
+    pub fn show_break_reminder(
+        &self,
+        reminder: &Reminder,
+        on_started: impl FnOnce() + 'static,
+    ) -> BreakResponse {
+        let maybe_hwnd = unsafe { self.get_hwnd() };
+        let Some(hwnd) = maybe_hwnd else {
+            return BreakResponse::Ended;
+        };
+
+        let title = HSTRING::from(format!("Break Reminder - {}", reminder.schedule));
+        let instruction = HSTRING::from(countdown_text(reminder.length));
+        let routine = reminder.routine.clone().map(|routine| RoutineState {
+            routine,
+            progress: reminder.routine_progress.clone(),
+            step: 0,
+            step_started: Instant::now(),
+            shown_seconds: None,
+        });
+        let content = match routine.as_ref() {
+            Some(routine) => HSTRING::from(routine.text(&reminder.message)),
+            None => HSTRING::from(&reminder.message),
+        };
+
+        let mut button_texts = Vec::new();
+        if let Some(length) = reminder.snooze_length {
+            let text = HSTRING::from(format!("Snooze {}", format_length(length)));
+            button_texts.push((SNOOZE_ID, text));
+        }
+        if routine.is_some() {
+            button_texts.push((PREVIOUS_STEP_ID, HSTRING::from("Previous step")));
+            button_texts.push((NEXT_STEP_ID, HSTRING::from("Next step")));
+        }
+        let buttons: Vec<_> = button_texts
+            .iter()
+            .map(|(id, text)| TASKDIALOG_BUTTON {
+                nButtonID: *id,
+                pszButtonText: PCWSTR(text.as_ptr()),
+            })
+            .collect();
+
+        let mut state = BreakDialogState {
+            started: Instant::now(),
+            length: reminder.length,
+            auto_close: reminder.auto_close,
+            enforce_length: reminder.enforce_length,
+            shown_seconds: None,
+            escalation_steps: reminder.escalation_steps.clone(),
+            escalation_level: reminder.escalation_level.clone(),
+            escalations_applied: 0,
+            owner: hwnd,
+            full_screen: false,
+            message: reminder.message.clone(),
+            routine,
+            on_started: Some(Box::new(on_started)),
+        };
+
+        let mut flags =
+            TDF_CALLBACK_TIMER | TDF_SHOW_PROGRESS_BAR | TDF_POSITION_RELATIVE_TO_WINDOW;
+        if !reminder.enforce_length {
+            flags |= TDF_ALLOW_DIALOG_CANCELLATION;
+        }
+
+        let config = TASKDIALOGCONFIG {
+            cbSize: size_of::<TASKDIALOGCONFIG>() as u32,
+            hwndParent: hwnd,
+            dwFlags: flags,
+            dwCommonButtons: TDCBF_OK_BUTTON,
+            pszWindowTitle: PCWSTR(title.as_ptr()),
+            Anonymous1: TASKDIALOGCONFIG_0 {
+                pszMainIcon: TD_INFORMATION_ICON,
+            },
+            pszMainInstruction: PCWSTR(instruction.as_ptr()),
+            pszContent: PCWSTR(content.as_ptr()),
+            cButtons: buttons.len() as u32,
+            pButtons: buttons.as_ptr(),
+            pfCallback: Some(break_dialog_callback),
+            lpCallbackData: std::ptr::from_mut(&mut state) as isize,
+            ..Default::default()
+        };
+
+        // The state outlives the dialog as this call blocks until the dialog is closed.
+        let mut button = 0;
+        let result = unsafe { TaskDialogIndirect(&config, Some(&mut button), None, None) };
+
+        if state.full_screen {
+            unsafe { state.leave_full_screen() };
+        }
+
+        if let Err(e) = result {
+            error!("Failed to show break reminder:\n{e}");
+            return BreakResponse::Ended;
+        }
+
+        match button {
+            SNOOZE_ID => BreakResponse::Snoozed,
+            _ => BreakResponse::Ended,
+        }
+    }
+}
+
+impl BreakDialogState {
+    /// Updates the countdown and progress bar, closing the dialog once the break is over if
+    /// `auto_close` is set.
+    unsafe fn update(&mut self, hwnd: HWND) {
+        let elapsed = self.started.elapsed();
+        let remaining = self.length.saturating_sub(elapsed);
+
+        // Round up so the countdown reaches zero as the break ends.
+        let remaining_seconds = remaining.as_millis().div_ceil(1000) as u64;
+        if self.shown_seconds == Some(remaining_seconds) {
+            return;
+        }
+        self.shown_seconds = Some(remaining_seconds);
+
+        let text = HSTRING::from(countdown_text(remaining));
+        SendMessageW(
+            hwnd,
+            TDM_SET_ELEMENT_TEXT.0 as u32,
+            WPARAM(TDE_MAIN_INSTRUCTION.0 as usize),
+            LPARAM(text.as_ptr() as isize),
+        );
+
+        let progress = if self.length.is_zero() {
+            PROGRESS_RANGE
+        } else {
+            let fraction = elapsed.as_secs_f64() / self.length.as_secs_f64();
+            (fraction.min(1.0) * PROGRESS_RANGE as f64) as u16
+        };
+        SendMessageW(
+            hwnd,
+            TDM_SET_PROGRESS_BAR_POS.0 as u32,
+            WPARAM(progress as usize),
+            LPARAM(0),
+        );
+
+        if remaining_seconds == 0 {
+            SendMessageW(
+                hwnd,
+                TDM_ENABLE_BUTTON.0 as u32,
+                WPARAM(IDOK.0 as usize),
+                LPARAM(1),
+            );
+
+            if self.auto_close {
+                SendMessageW(
+                    hwnd,
+                    TDM_CLICK_BUTTON.0 as u32,
+                    WPARAM(IDOK.0 as usize),
+                    LPARAM(0),
+                );
+            }
+        }
+    }
+
+    /// Applies any escalation steps the notifier has reached since the last update.
+    unsafe fn escalate(&mut self, hwnd: HWND) {
+        let level = self.escalation_level.get().min(self.escalation_steps.len());
+
+        while self.escalations_applied < level {
+            let step = self.escalation_steps[self.escalations_applied];
+            self.escalations_applied += 1;
+
+            match step {
+                EscalationStep::Renotify => {
+                    bring_to_front(hwnd);
+
+                    let flash = FLASHWINFO {
+                        cbSize: size_of::<FLASHWINFO>() as u32,
+                        hwnd,
+                        dwFlags: FLASHW_ALL | FLASHW_TIMERNOFG,
+                        uCount: 0,
+                        dwTimeout: 0,
+                    };
+                    let _ = FlashWindowEx(&flash);
+                }
+
+                EscalationStep::Sound => {
+                    bring_to_front(hwnd);
+
+                    if let Err(e) = MessageBeep(MB_ICONWARNING) {
+                        error!("Failed to play break reminder sound:\n{e}");
+                    }
+                }
+
+                EscalationStep::FullScreen => {
+                    self.enter_full_screen(hwnd);
+                    bring_to_front(hwnd);
+                }
+            }
+        }
+    }
+
+    /// Covers the monitor the dialog is on with the owner window, the dialog stays above it.
+    unsafe fn enter_full_screen(&mut self, hwnd: HWND) {
+        let monitor = MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST);
+        let mut info = MONITORINFO {
+            cbSize: size_of::<MONITORINFO>() as u32,
+            ..Default::default()
+        };
+        if !GetMonitorInfoW(monitor, &mut info).as_bool() {
+            error!("Failed to get the monitor of the break reminder");
+            return;
+        }
+
+        let bounds = info.rcMonitor;
+        if let Err(e) = SetWindowPos(
+            self.owner,
+            HWND_TOPMOST,
+            bounds.left,
+            bounds.top,
+            bounds.right - bounds.left,
+            bounds.bottom - bounds.top,
+            SWP_SHOWWINDOW,
+        ) {
+            error!("Failed to make break reminder full screen:\n{e}");
+            return;
+        }
+
+        self.full_screen = true;
+    }
+
+    /// Hides the owner window again after the dialog has closed.
+    unsafe fn leave_full_screen(&mut self) {
+        if let Err(e) = SetWindowPos(
+            self.owner,
+            HWND_NOTOPMOST,
+            0,
+            0,
+            0,
+            0,
+            SWP_NOMOVE | SWP_NOSIZE,
+        ) {
+            error!("Failed to restore break reminder window:\n{e}");
+        }
+        let _ = ShowWindow(self.owner, SW_HIDE);
+
+        self.full_screen = false;
+    }
+
+    /// Moves the routine on once the current step's time is up and updates the step countdown.
+    unsafe fn update_routine(&mut self, hwnd: HWND) {
+        let Some(routine) = self.routine.as_mut() else {
+            return;
+        };
+
+        if !routine.is_completed() && routine.step_remaining().is_zero() {
+            routine.complete_step();
+        }
+
+        routine.show(hwnd, &self.message, false);
+    }
+
+    /// Handles the previous and next step buttons, returns if the button was one of them.
+    unsafe fn click_routine_button(&mut self, hwnd: HWND, button: i32) -> bool {
+        let Some(routine) = self.routine.as_mut() else {
+            return false;
+        };
+
+        match button {
+            PREVIOUS_STEP_ID => routine.previous_step(),
+            NEXT_STEP_ID => routine.complete_step(),
+            _ => return false,
+        }
+
+        routine.show(hwnd, &self.message, true);
+        true
+    }
+
+    /// Returns if the break length has passed.
+    fn is_over(&self) -> bool {
+        self.started.elapsed() >= self.length
+    }
+}
+
+impl RoutineState {
+    /// How long is left of the step being shown, zero once the routine is completed.
+    fn step_remaining(&self) -> Duration {
+        if self.is_completed() {
+            return Duration::ZERO;
+        }
+
+        let Some(step) = self.routine.steps.get(self.step) else {
+            return Duration::ZERO;
+        };
+        step.length.saturating_sub(self.step_started.elapsed())
+    }
+
+    /// Returns if every step has been completed.
+    fn is_completed(&self) -> bool {
+        self.progress.get() >= self.routine.steps.len()
+    }
+
+    /// Records the step being shown as completed and moves on to the next one.
+    fn complete_step(&mut self) {
+        self.progress.set(self.progress.get().max(self.step + 1));
+
+        if self.step + 1 < self.routine.steps.len() {
+            self.step += 1;
+            self.step_started = Instant::now();
+        }
+    }
+
+    /// Goes back to the previous step and restarts its time.
+    fn previous_step(&mut self) {
+        self.step = self.step.saturating_sub(1);
+        self.step_started = Instant::now();
+    }
+
+    /// The reminder text followed by the step being shown.
+    fn text(&self, message: &str) -> String {
+        if self.is_completed() {
+            return format!("{message}\n\nRoutine \"{}\" complete.", self.routine.name);
+        }
+
+        let step = &self.routine.steps[self.step];
+        let seconds = self.step_remaining().as_millis().div_ceil(1000) as u64;
+        format!(
+            "{message}\n\n{}, step {} of {}: {} ({}:{:02})",
+            self.routine.name,
+            self.step + 1,
+            self.routine.steps.len(),
+            step.text,
+            seconds / 60,
+            seconds % 60
+        )
+    }
+
+    /// Shows the step and enables the buttons that apply to it, only updating the text when the
+    /// countdown changes unless `force` is set.
+    unsafe fn show(&mut self, hwnd: HWND, message: &str, force: bool) {
+        let remaining_seconds = self.step_remaining().as_millis().div_ceil(1000) as u64;
+        if !force && self.shown_seconds == Some(remaining_seconds) {
+            return;
+        }
+        self.shown_seconds = Some(remaining_seconds);
+
+        let text = HSTRING::from(self.text(message));
+        SendMessageW(
+            hwnd,
+            TDM_SET_ELEMENT_TEXT.0 as u32,
+            WPARAM(TDE_CONTENT.0 as usize),
+            LPARAM(text.as_ptr() as isize),
+        );
+
+        SendMessageW(
+            hwnd,
+            TDM_ENABLE_BUTTON.0 as u32,
+            WPARAM(PREVIOUS_STEP_ID as usize),
+            LPARAM((self.step > 0) as isize),
+        );
+        SendMessageW(
+            hwnd,
+            TDM_ENABLE_BUTTON.0 as u32,
+            WPARAM(NEXT_STEP_ID as usize),
+            LPARAM(!self.is_completed() as isize),
+        );
+    }
+}
+
+/// The text shown above the reminder message.
+fn countdown_text(remaining: Duration) -> String {
+    if remaining.is_zero() {
+        return "Break over".to_string();
+    }
+
+    let seconds = remaining.as_millis().div_ceil(1000) as u64;
+    format!("Break ends in {}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// Raises the dialog above other windows and gives it focus.
+unsafe fn bring_to_front(hwnd: HWND) {
+    if let Err(e) = SetWindowPos(
+        hwnd,
+        HWND_TOPMOST,
+        0,
+        0,
+        0,
+        0,
+        SWP_NOMOVE | SWP_NOSIZE | SWP_SHOWWINDOW,
+    ) {
+        error!("Failed to bring break reminder to the front:\n{e}");
+    }
+    let _ = SetForegroundWindow(hwnd);
+}
+
+/// Callback used by the break reminder task dialog, drives the countdown.
+unsafe extern "system" fn break_dialog_callback(
+    hwnd: HWND,
+    message: TASKDIALOG_NOTIFICATIONS,
+    wparam: WPARAM,
+    _lparam: LPARAM,
+    data: isize,
+) -> HRESULT {
+    // The data is a *mut BreakDialogState owned by `show_break_reminder` for the dialog's lifetime.
+    let state = &mut *(data as *mut BreakDialogState);
+
+    match message {
+        TDN_CREATED => {
+            // Keep the reminder above other windows, the same as the previous message box.
+            if let Err(e) = SetWindowPos(hwnd, HWND_TOPMOST, 0, 0, 0, 0, SWP_NOMOVE | SWP_NOSIZE) {
+                error!("Failed to make break reminder topmost:\n{e}");
+            }
+
+            SendMessageW(
+                hwnd,
+                TDM_SET_PROGRESS_BAR_RANGE.0 as u32,
+                WPARAM(0),
+                LPARAM(((PROGRESS_RANGE as u32) << 16) as isize),
+            );
+
+            if state.enforce_length {
+                SendMessageW(
+                    hwnd,
+                    TDM_ENABLE_BUTTON.0 as u32,
+                    WPARAM(IDOK.0 as usize),
+                    LPARAM(0),
+                );
+            }
+
+            state.update(hwnd);
+            state.update_routine(hwnd);
+            state.escalate(hwnd);
+
+            if let Some(on_started) = state.on_started.take() {
+                on_started();
+            }
+        }
+
+        TDN_TIMER => {
+            state.update(hwnd);
+            state.update_routine(hwnd);
+            state.escalate(hwnd);
+        }
+
+        // The routine buttons move between steps without closing the dialog.
+        TDN_BUTTON_CLICKED if state.click_routine_button(hwnd, wparam.0 as i32) => return S_FALSE,
+
+        // Returning S_FALSE keeps the dialog open, snoozing is always allowed.
+        TDN_BUTTON_CLICKED
+            if wparam.0 != SNOOZE_ID as usize && state.enforce_length && !state.is_over() =>
+        {
+            return S_FALSE
+        }
+
+        _ => {}
+    }
+
+    S_OK
+}
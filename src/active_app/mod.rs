@@ -1,52 +1,42 @@
+mod break_dialog;
 mod interval_dialog;
 mod pause_dialog;
+mod pomodoro_dialog;
 mod tray_icon;
 mod window;
 
+pub use break_dialog::BreakResponse;
+
 use thiserror::Error;
 
+use self::tray_icon::TrayMenu;
 use ::tray_icon::TrayIcon;
-use windows::{
-    core::{h, HSTRING},
-    Win32::UI::WindowsAndMessaging::{MessageBoxW, MB_ICONINFORMATION, MB_TOPMOST},
-};
 use winit::{event_loop::ActiveEventLoop, window::Window};
 
 /// An active initialized app that depends on the event loop.
 pub struct ActiveApp {
     pub window: Window,
 
-    #[allow(unused)]
     pub tray_icon: TrayIcon,
+
+    pub tray_menu: TrayMenu,
 }
 
 impl ActiveApp {
-    pub fn new(event_loop: &ActiveEventLoop) -> Result<Self, Error> {
+    pub fn new(event_loop: &ActiveEventLoop, pomodoro: bool) -> Result<Self, Error> {
         let window = Self::create_window(event_loop)?;
-        let tray_icon = Self::create_tray_icon()?;
+        let (tray_icon, tray_menu) = Self::create_tray_icon(pomodoro)?;
 
-        let app = Self { tray_icon, window };
+        let app = Self {
+            tray_icon,
+            tray_menu,
+            window,
+        };
 
         app.move_window_to_best_monitor();
 
         Ok(app)
     }
-
-    /// Shows the break reminder
-    pub fn show_break_reminder(&self) {
-        let maybe_hwnd = unsafe { self.get_hwnd() };
-        let Some(hwnd) = maybe_hwnd else { return };
-
-        let message = HSTRING::from("Take a break, stand up, drink some water, stretch.");
-        unsafe {
-            MessageBoxW(
-                Some(&hwnd),
-                &message,
-                h!("Break Reminder"),
-                MB_ICONINFORMATION | MB_TOPMOST,
-            );
-        }
-    }
 }
 
 #[derive(Debug, Error)]
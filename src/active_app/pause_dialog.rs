@@ -1,14 +1,19 @@
 use std::time::Duration;
 
-use tracing::error;
+use chrono::NaiveTime;
+use tracing::{error, warn};
 use windows::Win32::{
     Foundation::{GetLastError, LPARAM},
-    UI::WindowsAndMessaging::DialogBoxIndirectParamW,
+    UI::WindowsAndMessaging::{DialogBoxIndirectParamW, MB_ICONWARNING},
 };
 
-use crate::dialog::{
-    number_input_dialog::{number_input_dialog_callback, NumberInputDialogTemplate},
-    OK_ID_ISIZE,
+use crate::{
+    dialog::{
+        number_input_dialog::{number_input_dialog_callback, NumberInputDialogTemplate},
+        text_input_dialog::{text_input_dialog_callback, TextInputDialogTemplate},
+        OK_ID_ISIZE,
+    },
+    message_box::message_box,
 };
 
 use super::ActiveApp;
@@ -24,6 +29,11 @@ const INPUT_TITLE: [u16; 30] = [
     0x0075, 0x0074, 0x0065, 0x0073, 0x0029, 0x0000,
 ];
 
+const UNTIL_INPUT_TITLE: [u16; 20] = [
+    0x0050, 0x0061, 0x0075, 0x0073, 0x0065, 0x0020, 0x0075, 0x006e, 0x0074, 0x0069, 0x006c, 0x0020,
+    0x0028, 0x0048, 0x0048, 0x003a, 0x004d, 0x004d, 0x0029, 0x0000,
+];
+
 const SUBMIT_TITLE: [u16; 14] = [
     0x0043, 0x006f, 0x006e, 0x0066, 0x0069, 0x0072, 0x006d, 0x0020, 0x0050, 0x0061, 0x0075, 0x0073,
     0x0065, 0x0000,
@@ -64,4 +74,44 @@ impl ActiveApp {
             }
         }
     }
+
+    /// Handles the pause until dialog, returns the time of day to pause until.
+    pub(super) fn show_pause_until_dialog(&self) -> Option<NaiveTime> {
+        unsafe {
+            let template =
+                TextInputDialogTemplate::new_text(DIALOG_TITLE, UNTIL_INPUT_TITLE, SUBMIT_TITLE);
+            let template_pointer = std::ptr::from_ref(&template.dialog.dialog_template);
+
+            let mut time = Box::new(String::new());
+            let time_ptr: *mut String = &mut *time;
+
+            let result = DialogBoxIndirectParamW(
+                None,
+                template_pointer,
+                None,
+                Some(text_input_dialog_callback),
+                LPARAM(time_ptr as isize),
+            );
+
+            match result {
+                // -1 is an win32 error
+                -1 => {
+                    let error = GetLastError().0;
+                    error!("Failure response from dialog:\n{error}");
+                    None
+                }
+
+                OK_ID_ISIZE => match NaiveTime::parse_from_str(time.trim(), "%H:%M") {
+                    Ok(time) => Some(time),
+                    Err(e) => {
+                        warn!("Invalid pause until time \"{time}\":\n{e}");
+                        message_box("Invalid time, enter a time like 14:00.", MB_ICONWARNING);
+                        None
+                    }
+                },
+
+                _ => None,
+            }
+        }
+    }
 }
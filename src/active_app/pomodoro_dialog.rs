@@ -0,0 +1,66 @@
+use tracing::error;
+use windows::Win32::{
+    Foundation::{GetLastError, LPARAM},
+    UI::WindowsAndMessaging::DialogBoxIndirectParamW,
+};
+
+use crate::dialog::{
+    text_input_dialog::{text_input_dialog_callback, TextInputDialogTemplate},
+    OK_ID_ISIZE,
+};
+
+use super::ActiveApp;
+
+const DIALOG_TITLE: [u16; 15] = [
+    0x0053, 0x0074, 0x0061, 0x0072, 0x0074, 0x0020, 0x0050, 0x006f, 0x006d, 0x006f, 0x0064, 0x006f,
+    0x0072, 0x006f, 0x0000,
+];
+
+const INPUT_TITLE: [u16; 22] = [
+    0x0054, 0x0061, 0x0073, 0x006b, 0x0020, 0x006c, 0x0061, 0x0062, 0x0065, 0x006c, 0x0020, 0x0028,
+    0x006f, 0x0070, 0x0074, 0x0069, 0x006f, 0x006e, 0x0061, 0x006c, 0x0029, 0x0000,
+];
+
+const SUBMIT_TITLE: [u16; 6] = [0x0053, 0x0074, 0x0061, 0x0072, 0x0074, 0x0000];
+
+impl ActiveApp {
+    /// Handles the start pomodoro dialog, returns the task label if the dialog was confirmed.
+    pub(super) fn show_pomodoro_dialog(&self) -> Option<Option<String>> {
+        unsafe {
+            let template =
+                TextInputDialogTemplate::new_text(DIALOG_TITLE, INPUT_TITLE, SUBMIT_TITLE);
+            let template_pointer = std::ptr::from_ref(&template.dialog.dialog_template);
+
+            let mut task = Box::new(String::new());
+            let task_ptr: *mut String = &mut *task;
+
+            let result = DialogBoxIndirectParamW(
+                None,
+                template_pointer,
+                None,
+                Some(text_input_dialog_callback),
+                LPARAM(task_ptr as isize),
+            );
+
+            match result {
+                // -1 is an win32 error
+                -1 => {
+                    let error = GetLastError().0;
+                    error!("Failure response from dialog:\n{error}");
+                    None
+                }
+
+                OK_ID_ISIZE => {
+                    let task = task.trim();
+                    if task.is_empty() {
+                        Some(None)
+                    } else {
+                        Some(Some(task.to_string()))
+                    }
+                }
+
+                _ => None,
+            }
+        }
+    }
+}
@@ -1,21 +1,60 @@
+use std::{
+    cell::{Cell, RefCell},
+    time::Duration,
+};
+
+use chrono::NaiveDateTime;
 use thiserror::Error;
 use tracing::{error, warn};
 use tray_icon::{
-    menu::{Menu, MenuEvent, MenuItem},
+    menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem, Submenu},
     TrayIcon, TrayIconBuilder,
 };
 use winit::event_loop::ActiveEventLoop;
 
-use crate::message::Message;
+use crate::{
+    message::Message,
+    pause::{describe_resume_time, PauseLength},
+    screen_time::format_hours,
+};
 
 use super::ActiveApp;
 
+/// The parts of the tray menu that change while the app is running.
+pub struct TrayMenu {
+    pub menu: Menu,
+
+    /// Shows when reminders resume, only in the menu while paused.
+    pub pause_status: MenuItem,
+
+    /// Ends the pause early, only in the menu while paused.
+    pub resume: MenuItem,
+
+    /// If the pause items are in the menu.
+    pub paused: Cell<bool>,
+
+    /// Today's screen time shown in the tooltip, `None` until the notifier sends it.
+    pub screen_time: Cell<Option<Duration>>,
+
+    /// Why reminders are held back, shown in the tooltip.
+    pub suppressed: RefCell<Option<String>>,
+}
+
 impl ActiveApp {
-    /// Creates the tray icon.
-    pub(super) fn create_tray_icon() -> Result<TrayIcon, CreateError> {
+    /// Creates the tray icon, with the pomodoro controls if a schedule is a pomodoro.
+    pub(super) fn create_tray_icon(pomodoro: bool) -> Result<(TrayIcon, TrayMenu), CreateError> {
         let tray_icon = tray_icon::Icon::from_resource(1, Some((24, 24)))?;
 
-        let pause_item = MenuItem::with_id("pause", "Pause breaks for...", true, None);
+        let pause_item = Submenu::with_items(
+            "Pause breaks",
+            true,
+            &[
+                &MenuItem::with_id("pause_for", "For...", true, None),
+                &MenuItem::with_id("pause_until", "Until...", true, None),
+                &MenuItem::with_id("pause_tomorrow", "Until tomorrow morning", true, None),
+                &MenuItem::with_id("pause_resumed", "Until resumed", true, None),
+            ],
+        )?;
         let interval_item = MenuItem::with_id("interval", "Set interval...", true, None);
         let debug_log_item = MenuItem::with_id("debug_log", "Log debug info", true, None);
         let debug_show_item = MenuItem::with_id("debug_show", "Show debug info", true, None);
@@ -29,15 +68,100 @@ impl ActiveApp {
             &quit_item,
         ])?;
 
-        let tooltip = format!("Break Reminder v{}", env!("CARGO_PKG_VERSION"));
+        if pomodoro {
+            let start_item = MenuItem::with_id("pomodoro_start", "Start pomodoro...", true, None);
+            let skip_item =
+                MenuItem::with_id("pomodoro_skip", "Skip to pomodoro break", true, None);
+            let reset_item = MenuItem::with_id("pomodoro_reset", "Reset pomodoro", true, None);
+
+            tray_menu.prepend_items(&[
+                &start_item,
+                &skip_item,
+                &reset_item,
+                &PredefinedMenuItem::separator(),
+            ])?;
+        }
 
         let tray_icon = TrayIconBuilder::new()
-            .with_menu(Box::new(tray_menu))
-            .with_tooltip(tooltip)
+            .with_menu(Box::new(tray_menu.clone()))
+            .with_tooltip(Self::tooltip())
             .with_icon(tray_icon)
             .build()?;
 
-        Ok(tray_icon)
+        let tray_menu = TrayMenu {
+            menu: tray_menu,
+            pause_status: MenuItem::with_id("pause_status", "", false, None),
+            resume: MenuItem::with_id("resume", "Resume now", true, None),
+            paused: Cell::new(false),
+            screen_time: Cell::new(None),
+            suppressed: RefCell::new(None),
+        };
+
+        Ok((tray_icon, tray_menu))
+    }
+
+    /// Shows when reminders resume and the resume item at the top of the tray menu.
+    pub fn show_paused(&self, resumes: Option<NaiveDateTime>) {
+        let tray_menu = &self.tray_menu;
+
+        let text = format!("Paused until {}", describe_resume_time(resumes));
+        tray_menu.pause_status.set_text(text);
+
+        if !tray_menu.paused.replace(true) {
+            let items = [&tray_menu.pause_status, &tray_menu.resume];
+            if let Err(e) = tray_menu.menu.prepend_items(&items.map(|item| item as _)) {
+                error!("Failed to show pause status:\n{e}");
+            }
+        }
+    }
+
+    /// The tooltip before anything is added to it.
+    fn tooltip() -> String {
+        format!("Break Reminder v{}", env!("CARGO_PKG_VERSION"))
+    }
+
+    /// Shows today's screen time in the tray icon's tooltip.
+    pub fn show_screen_time(&self, active: Duration) {
+        self.tray_menu.screen_time.set(Some(active));
+        self.update_tooltip();
+    }
+
+    /// Shows why reminders are held back in the tray icon's tooltip.
+    pub fn show_suppressed(&self, reason: Option<String>) {
+        self.tray_menu.suppressed.replace(reason);
+        self.update_tooltip();
+    }
+
+    /// Sets the tooltip to the version, today's screen time, and why reminders are held back.
+    fn update_tooltip(&self) {
+        let mut tooltip = Self::tooltip();
+
+        if let Some(active) = self.tray_menu.screen_time.get() {
+            tooltip.push_str(&format!("\nScreen time today: {}", format_hours(active)));
+        }
+
+        if let Some(reason) = self.tray_menu.suppressed.borrow().as_ref() {
+            tooltip.push_str(&format!("\nReminders suppressed: {reason}"));
+        }
+
+        if let Err(e) = self.tray_icon.set_tooltip(Some(tooltip)) {
+            error!("Failed to update tooltip:\n{e}");
+        }
+    }
+
+    /// Removes the pause status and resume item from the tray menu.
+    pub fn show_resumed(&self) {
+        let tray_menu = &self.tray_menu;
+
+        if !tray_menu.paused.replace(false) {
+            return;
+        }
+
+        for item in [&tray_menu.pause_status, &tray_menu.resume] {
+            if let Err(e) = tray_menu.menu.remove(item) {
+                error!("Failed to remove pause item:\n{e}");
+            }
+        }
     }
 
     /// Handles any tray icon events.
@@ -47,10 +171,28 @@ impl ActiveApp {
         };
 
         match event.id.0.as_str() {
-            "pause" => self.show_pause_dialog().map(Message::PauseReminders),
+            "pause_for" => self
+                .show_pause_dialog()
+                .map(|duration| Message::PauseReminders(PauseLength::For(duration))),
+
+            "pause_until" => self
+                .show_pause_until_dialog()
+                .map(|time| Message::PauseReminders(PauseLength::Until(time))),
+
+            "pause_tomorrow" => Some(Message::PauseReminders(PauseLength::TomorrowMorning)),
+
+            "pause_resumed" => Some(Message::PauseReminders(PauseLength::UntilResumed)),
+
+            "resume" => Some(Message::Resume),
 
             "interval" => self.show_interval_dialog().map(Message::SetInterval),
 
+            "pomodoro_start" => self.show_pomodoro_dialog().map(Message::StartPomodoro),
+
+            "pomodoro_skip" => Some(Message::SkipPomodoro),
+
+            "pomodoro_reset" => Some(Message::ResetPomodoro),
+
             "quit" => {
                 event_loop.exit();
                 None
@@ -0,0 +1,207 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    break_reminder::{minutes_since, Break},
+    config::AdaptiveConfig,
+};
+
+/// Adaptive interval state for a schedule, adjusts the interval within bounds depending on how
+/// recent breaks were taken.
+pub struct Adaptive {
+    /// The configured interval, reminders drift back towards it while breaks are taken in full.
+    pub base: Duration,
+
+    /// The shortest the interval can become.
+    pub min_interval: Duration,
+
+    /// The longest the interval can become.
+    pub max_interval: Duration,
+
+    /// How much a single adjustment changes the interval by.
+    pub step: Duration,
+
+    /// How many ended breaks are looked at before adjusting.
+    pub history: usize,
+
+    /// How the breaks since the last adjustment were taken, oldest first.
+    pub recent: VecDeque<BreakOutcome>,
+
+    /// The most recent adjustment, if any.
+    pub last_adjustment: Option<Adjustment>,
+}
+
+/// How a break was taken.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BreakOutcome {
+    /// The break was taken in full without being snoozed.
+    Taken,
+
+    /// The break was taken in full after being snoozed.
+    Snoozed,
+
+    /// The break ended before its length had passed.
+    CutShort,
+
+    /// The break was dismissed before a quarter of its length had passed.
+    Skipped,
+}
+
+/// A change made to the interval.
+pub struct Adjustment {
+    /// The interval before the adjustment.
+    pub from: Duration,
+
+    /// The interval after the adjustment.
+    pub to: Duration,
+
+    /// Why the interval was adjusted.
+    pub reason: String,
+
+    /// When the adjustment was made.
+    pub at: Instant,
+}
+
+impl Adaptive {
+    /// Constructs the adaptive state for a schedule with the given configured interval.
+    pub fn new(config: &AdaptiveConfig, base: Duration) -> Self {
+        Self {
+            base,
+            min_interval: config.min_interval,
+            max_interval: config.max_interval,
+            step: config.step,
+            history: config.history.max(1),
+            recent: VecDeque::new(),
+            last_adjustment: None,
+        }
+    }
+
+    /// Replaces the configured interval, behaviour recorded so far no longer applies.
+    pub fn set_base(&mut self, base: Duration) {
+        self.base = base;
+        self.recent.clear();
+    }
+
+    /// Records how the ended break was taken and returns the adjustment to make to `interval`,
+    /// if recent behaviour calls for one.
+    pub fn record(
+        &mut self,
+        ended: &Break,
+        interval: Duration,
+        now: Instant,
+    ) -> Option<&Adjustment> {
+        self.recent.push_back(BreakOutcome::of(ended));
+        if self.recent.len() < self.history {
+            return None;
+        }
+
+        let count = |outcomes: &[BreakOutcome]| {
+            self.recent
+                .iter()
+                .filter(|outcome| outcomes.contains(outcome))
+                .count()
+        };
+        let missed = count(&[BreakOutcome::CutShort, BreakOutcome::Skipped]);
+        let snoozed = count(&[BreakOutcome::Snoozed]);
+        let taken = count(&[BreakOutcome::Taken]);
+        let total = self.recent.len();
+
+        // Missed breaks mean rest is not happening, so it is offered sooner. Snoozes mean
+        // reminders arrive at bad times, so work periods are given more room.
+        let (target, reason) = if missed * 2 > total {
+            (
+                interval.saturating_sub(self.step),
+                format!("{missed} of the last {total} breaks were skipped or cut short"),
+            )
+        } else if snoozed * 2 > total {
+            (
+                interval + self.step,
+                format!("{snoozed} of the last {total} breaks were snoozed"),
+            )
+        } else if taken == total && interval > self.base {
+            (
+                interval.saturating_sub(self.step).max(self.base),
+                format!("the last {total} breaks were taken in full"),
+            )
+        } else if taken == total && interval < self.base {
+            (
+                (interval + self.step).min(self.base),
+                format!("the last {total} breaks were taken in full"),
+            )
+        } else {
+            // Without a reason the interval is left as it is, even outside of the bounds.
+            self.recent.pop_front();
+            return None;
+        };
+
+        let target = target.clamp(self.min_interval, self.max_interval);
+        if target == interval {
+            self.recent.pop_front();
+            return None;
+        }
+
+        self.recent.clear();
+        self.last_adjustment = Some(Adjustment {
+            from: interval,
+            to: target,
+            reason,
+            at: now,
+        });
+
+        self.last_adjustment.as_ref()
+    }
+
+    /// Describes the adaptive state relative to `now`.
+    pub fn describe(&self, now: Instant) -> String {
+        let recent: Vec<_> = self
+            .recent
+            .iter()
+            .map(|outcome| format!("{outcome:?}"))
+            .collect();
+        let mut description = format!(
+            "Adaptive {{ base: {} minutes, range: {}-{} minutes, recent: [{}] }}",
+            self.base.as_secs() / 60,
+            self.min_interval.as_secs() / 60,
+            self.max_interval.as_secs() / 60,
+            recent.join(", ")
+        );
+
+        if let Some(adjustment) = self.last_adjustment.as_ref() {
+            description.push_str(&format!("\n{}", adjustment.describe(now)));
+        }
+
+        description
+    }
+}
+
+impl BreakOutcome {
+    /// Classifies how the ended break was taken.
+    pub fn of(ended: &Break) -> Self {
+        let taken = ended.taken().unwrap_or_default();
+
+        if taken < ended.length / 4 {
+            Self::Skipped
+        } else if ended.was_cut_short() {
+            Self::CutShort
+        } else if ended.snoozes > 0 {
+            Self::Snoozed
+        } else {
+            Self::Taken
+        }
+    }
+}
+
+impl Adjustment {
+    /// Describes the adjustment relative to `now`.
+    pub fn describe(&self, now: Instant) -> String {
+        format!(
+            "  Adjusted from {} to {} minutes {} minutes ago, {}",
+            self.from.as_secs() / 60,
+            self.to.as_secs() / 60,
+            minutes_since(self.at, now),
+            self.reason
+        )
+    }
+}
@@ -0,0 +1,57 @@
+use std::time::Duration;
+
+use chrono::{NaiveDateTime, NaiveTime, TimeDelta};
+
+use crate::config::AlignmentConfig;
+
+/// Aligns a schedule's reminders to wall-clock slots, every interval from local midnight plus an
+/// offset, e.g. :00 and :30 for a 30 minute interval.
+#[derive(Clone, Debug)]
+pub struct Alignment {
+    /// How far the slots are shifted from midnight, less than the interval.
+    pub offset: Duration,
+
+    /// The shortest time between a break ending and the next reminder, closer slots are skipped.
+    pub min_gap: Duration,
+}
+
+impl Alignment {
+    /// Constructs the alignment from its config.
+    pub fn new(config: &AlignmentConfig) -> Self {
+        Self {
+            offset: config.offset,
+            min_gap: config.min_gap,
+        }
+    }
+
+    /// Returns the first slot at or after `earliest` for slots every `interval`.
+    pub fn next_slot(&self, interval: Duration, earliest: NaiveDateTime) -> NaiveDateTime {
+        let interval = (interval.as_millis() as i64).max(1);
+        let offset = self.offset.as_millis() as i64 % interval;
+
+        let midnight = earliest.date().and_time(NaiveTime::MIN);
+        let since_first = (earliest - midnight).num_milliseconds() - offset;
+        let slots =
+            since_first.div_euclid(interval) + i64::from(since_first.rem_euclid(interval) != 0);
+
+        midnight + TimeDelta::milliseconds(offset + slots * interval)
+    }
+
+    /// Returns when the reminder is due for an interval that started at the local time `from`.
+    ///
+    /// The reminder is at the first slot at least `min_gap` after `from`, so a break taken late
+    /// or starting up just before a slot waits for the following one.
+    pub fn next_due(&self, from: NaiveDateTime, interval: Duration) -> NaiveDateTime {
+        let min_gap = TimeDelta::from_std(self.min_gap).unwrap_or_default();
+        self.next_slot(interval, from + min_gap)
+    }
+
+    /// Describes the alignment.
+    pub fn describe(&self) -> String {
+        format!(
+            "Aligned to the clock with a {} minute offset, at least {} minutes after a break",
+            self.offset.as_secs() / 60,
+            self.min_gap.as_secs() / 60
+        )
+    }
+}
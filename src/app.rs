@@ -2,10 +2,16 @@ use std::sync::mpsc::Sender;
 
 use tracing::error;
 use uuid::Uuid;
-use windows::Win32::UI::WindowsAndMessaging::MB_ICONERROR;
+use windows::Win32::UI::WindowsAndMessaging::{MB_ICONERROR, MB_ICONWARNING};
 use winit::{application::ApplicationHandler, event::WindowEvent, event_loop::ActiveEventLoop};
 
-use crate::{active_app::ActiveApp, message::Message, message_box::message_box};
+use crate::{
+    active_app::{ActiveApp, BreakResponse},
+    break_reminder::Reminder,
+    message::{Message, NotifierEvent},
+    message_box::message_box,
+    screen_time::format_hours,
+};
 
 /// The core app
 pub struct App {
@@ -17,14 +23,18 @@ pub struct App {
 
     /// The sender to contant the notifer.
     pub message_sender: Sender<Message>,
+
+    /// If the pomodoro controls should be shown.
+    pub pomodoro: bool,
 }
 
 impl App {
-    pub fn new(message_sender: Sender<Message>) -> Self {
+    pub fn new(message_sender: Sender<Message>, pomodoro: bool) -> Self {
         Self {
             active_app: None,
             break_id: None,
             message_sender,
+            pomodoro,
         }
     }
 
@@ -40,23 +50,52 @@ impl App {
         }
     }
 
+    /// Snoozes the break so the notifier sends it again later.
+    pub fn snooze_break(&mut self) {
+        if let Some(break_id) = self.break_id.take() {
+            if let Err(e) = self.message_sender.send(Message::Snooze(break_id)) {
+                error!("Failed to send break snooze to waker thread:\n{e}");
+                message_box("Failed to send break snooze to waker thread.", MB_ICONERROR);
+                panic!("Failed to send break snooze to waker thread:\n{e}");
+            }
+        }
+    }
+
     /// Tries to show the break reminder, if app isn't active then the break is ended.
-    pub fn show_break_reminder(&mut self, id: Uuid) {
+    pub fn show_break_reminder(&mut self, reminder: Reminder) {
+        self.break_id = Some(reminder.break_id);
+
         let Some(app) = self.active_app.as_ref() else {
             self.finish_break();
             return;
         };
 
+        if let Err(e) = self
+            .message_sender
+            .send(Message::AcknowledgeBreak(reminder.break_id))
+        {
+            error!("Failed to send break acknowledgement to waker thread:\n{e}");
+        }
+
+        let message_sender = self.message_sender.clone();
+        let on_started = move || {
+            if let Err(e) = message_sender.send(Message::StartBreak(reminder.break_id)) {
+                error!("Failed to send break start to waker thread:\n{e}");
+            }
+        };
+
         app.move_window_to_best_monitor();
-        self.break_id = Some(id);
-        app.show_break_reminder(); // blocking
-        self.finish_break();
+        let response = app.show_break_reminder(&reminder, on_started); // blocking
+        match response {
+            BreakResponse::Ended => self.finish_break(),
+            BreakResponse::Snoozed => self.snooze_break(),
+        }
     }
 }
 
-impl ApplicationHandler<Uuid> for App {
+impl ApplicationHandler<NotifierEvent> for App {
     fn resumed(&mut self, event_loop: &ActiveEventLoop) {
-        match ActiveApp::new(event_loop) {
+        match ActiveApp::new(event_loop, self.pomodoro) {
             Ok(app) => self.active_app = Some(app),
             Err(e) => {
                 error!("Failed to initialize app:\n{e}");
@@ -93,8 +132,43 @@ impl ApplicationHandler<Uuid> for App {
         }
     }
 
-    fn user_event(&mut self, _event_loop: &ActiveEventLoop, event: Uuid) {
-        self.show_break_reminder(event);
+    fn user_event(&mut self, _event_loop: &ActiveEventLoop, event: NotifierEvent) {
+        match event {
+            NotifierEvent::Reminder(reminder) => self.show_break_reminder(*reminder),
+
+            NotifierEvent::Paused(resumes) => {
+                if let Some(app) = self.active_app.as_ref() {
+                    app.show_paused(resumes);
+                }
+            }
+
+            NotifierEvent::Resumed => {
+                if let Some(app) = self.active_app.as_ref() {
+                    app.show_resumed();
+                }
+            }
+
+            NotifierEvent::ScreenTime(active) => {
+                if let Some(app) = self.active_app.as_ref() {
+                    app.show_screen_time(active);
+                }
+            }
+
+            NotifierEvent::Suppressed(reason) => {
+                if let Some(app) = self.active_app.as_ref() {
+                    app.show_suppressed(reason);
+                }
+            }
+
+            NotifierEvent::DailyLimit { active, budget } => {
+                let message = format!(
+                    "You have been at the computer for {} today, over your daily budget of {}.\n\nTime to wrap up for the day.",
+                    format_hours(active),
+                    format_hours(budget)
+                );
+                message_box(message, MB_ICONWARNING);
+            }
+        }
     }
 
     fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
@@ -0,0 +1,177 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    break_reminder::{format_length, minutes_since},
+    config::BreakDebtConfig,
+};
+
+/// How many changes to the debt are kept in the history.
+const HISTORY_LENGTH: usize = 20;
+
+/// The break time owed from breaks that were missed, repaid by making later breaks longer.
+pub struct BreakDebt {
+    /// The break time currently owed.
+    pub owed: Duration,
+
+    /// The most break time that can be owed.
+    pub max_debt: Duration,
+
+    /// The most a single break is made longer by, as a percentage of its length.
+    pub max_extension: u32,
+
+    /// The latest changes to the debt, oldest first.
+    pub history: VecDeque<DebtChange>,
+}
+
+/// Why the debt changed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DebtReason {
+    /// A break ended before its length had passed.
+    Skipped,
+
+    /// A snoozed break was abandoned without being taken.
+    Expired,
+
+    /// A break came due during a pause and was dropped when the pause ended.
+    Paused,
+
+    /// A longer break was taken in full.
+    Repaid,
+}
+
+impl DebtReason {
+    /// Describes what happened to the break.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Skipped => "skipped",
+            Self::Expired => "abandoned while snoozed",
+            Self::Paused => "dropped by a pause",
+            Self::Repaid => "taken with extra time",
+        }
+    }
+}
+
+/// A change to the debt.
+#[derive(Clone, Debug)]
+pub struct DebtChange {
+    /// The schedule the break belonged to.
+    pub schedule: String,
+
+    /// Why the debt changed.
+    pub reason: DebtReason,
+
+    /// How much break time was owed or repaid.
+    pub amount: Duration,
+
+    /// The break time owed after the change.
+    pub owed: Duration,
+
+    /// When the debt changed.
+    pub at: Instant,
+}
+
+impl BreakDebt {
+    /// Constructs an empty ledger from its config.
+    pub fn new(config: &BreakDebtConfig) -> Self {
+        Self {
+            owed: Duration::ZERO,
+            max_debt: config.max_debt,
+            max_extension: config.max_extension,
+            history: VecDeque::new(),
+        }
+    }
+
+    /// Returns how much longer a break of `length` is made to repay the debt.
+    pub fn extension(&self, length: Duration) -> Duration {
+        let max_extension = length * self.max_extension / 100;
+        self.owed.min(max_extension)
+    }
+
+    /// Adds the missed break time to the debt, up to the maximum debt. Returns the change, `None`
+    /// if nothing was missed.
+    pub fn owe(
+        &mut self,
+        schedule: &str,
+        reason: DebtReason,
+        amount: Duration,
+        now: Instant,
+    ) -> Option<&DebtChange> {
+        if amount.is_zero() {
+            return None;
+        }
+
+        self.owed = (self.owed + amount).min(self.max_debt);
+        self.record(schedule, reason, amount, now)
+    }
+
+    /// Takes the extra break time taken off the debt. Returns the change, `None` if nothing was
+    /// repaid.
+    pub fn repay(&mut self, schedule: &str, amount: Duration, now: Instant) -> Option<&DebtChange> {
+        if amount.is_zero() {
+            return None;
+        }
+
+        self.owed = self.owed.saturating_sub(amount);
+        self.record(schedule, DebtReason::Repaid, amount, now)
+    }
+
+    /// Adds a change to the history, dropping the oldest once it is full.
+    fn record(
+        &mut self,
+        schedule: &str,
+        reason: DebtReason,
+        amount: Duration,
+        now: Instant,
+    ) -> Option<&DebtChange> {
+        if self.history.len() >= HISTORY_LENGTH {
+            self.history.pop_front();
+        }
+
+        self.history.push_back(DebtChange {
+            schedule: schedule.to_string(),
+            reason,
+            amount,
+            owed: self.owed,
+            at: now,
+        });
+        self.history.back()
+    }
+
+    /// Describes the debt and its latest changes relative to `now`.
+    pub fn describe(&self, now: Instant) -> String {
+        let changes: Vec<_> = self
+            .history
+            .iter()
+            .rev()
+            .take(5)
+            .map(|change| {
+                let sign = if change.reason == DebtReason::Repaid {
+                    '-'
+                } else {
+                    '+'
+                };
+                format!(
+                    "{} break {} {} minutes ago {sign}{}",
+                    change.schedule,
+                    change.reason.name(),
+                    minutes_since(change.at, now),
+                    format_length(change.amount)
+                )
+            })
+            .collect();
+
+        let mut description = format!(
+            "Break debt: {} of at most {} owed",
+            format_length(self.owed),
+            format_length(self.max_debt)
+        );
+        if !changes.is_empty() {
+            description.push_str(&format!("\n  {}", changes.join(", ")));
+        }
+
+        description
+    }
+}
@@ -1,52 +1,486 @@
-use std::{fmt::Display, time::Instant};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
 
+use thiserror::Error;
 use uuid::Uuid;
 
+use crate::{config::EscalationStep, routine::Routine};
+
+/// A reminder sent from the notifier to the event loop.
+#[derive(Clone, Debug)]
+pub struct Reminder {
+    /// The id of the break the reminder is for.
+    pub break_id: Uuid,
+
+    /// The name of the schedule the break belongs to.
+    pub schedule: String,
+
+    /// The reminder text.
+    pub message: String,
+
+    /// How long the break should last, shown as a countdown.
+    pub length: Duration,
+
+    /// If the reminder should close itself once the break is over.
+    pub auto_close: bool,
+
+    /// If the reminder cannot be dismissed until the break is over.
+    pub enforce_length: bool,
+
+    /// How long the break can be snoozed for, `None` once the snooze limit has been reached.
+    pub snooze_length: Option<Duration>,
+
+    /// The steps the reminder escalates through while it is left open.
+    pub escalation_steps: Vec<EscalationStep>,
+
+    /// How many of the escalation steps the notifier has reached.
+    pub escalation_level: EscalationLevel,
+
+    /// The routine to walk through during the break, if any.
+    pub routine: Option<Routine>,
+
+    /// How many steps of the routine have been completed, updated by the reminder.
+    pub routine_progress: RoutineProgress,
+}
+
+/// How many escalation steps an open reminder has reached, shared between the notifier and the
+/// reminder so the reminder can escalate while it blocks the event loop.
+#[derive(Clone, Debug, Default)]
+pub struct EscalationLevel(Arc<AtomicUsize>);
+
+impl EscalationLevel {
+    /// The number of steps reached.
+    pub fn get(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    fn set(&self, level: usize) {
+        self.0.store(level, Ordering::Relaxed);
+    }
+}
+
+/// How many steps of a routine have been completed, shared between the notifier and the
+/// reminder that walks through the routine.
+#[derive(Clone, Debug, Default)]
+pub struct RoutineProgress(Arc<AtomicUsize>);
+
+impl RoutineProgress {
+    /// The number of steps completed.
+    pub fn get(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Records the number of steps completed.
+    pub fn set(&self, completed: usize) {
+        self.0.store(completed, Ordering::Relaxed);
+    }
+}
+
+/// The routine walked through during a break.
+#[derive(Clone, Debug)]
+pub struct BreakRoutine {
+    /// The name of the routine.
+    pub name: String,
+
+    /// How many steps the routine has.
+    pub steps: usize,
+
+    /// How many steps have been completed.
+    pub progress: RoutineProgress,
+}
+
+impl BreakRoutine {
+    /// Constructs the record for a routine that has not been started.
+    pub fn new(routine: &Routine) -> Self {
+        Self {
+            name: routine.name.clone(),
+            steps: routine.steps.len(),
+            progress: RoutineProgress::default(),
+        }
+    }
+
+    /// Returns if every step of the routine was completed.
+    pub fn is_completed(&self) -> bool {
+        self.progress.get() >= self.steps
+    }
+}
+
+/// An escalation step reached by an ignored reminder.
+#[derive(Clone, Copy, Debug)]
+pub struct Escalation {
+    /// The step reached.
+    pub step: EscalationStep,
+
+    /// When the step was reached.
+    pub at: Instant,
+}
+
+/// Where a break is in its lifecycle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BreakState {
+    /// The break is waiting for its interval to pass.
+    Scheduled,
+
+    /// The reminder has been sent to the event loop.
+    Notified,
+
+    /// The event loop has shown the reminder.
+    Acknowledged,
+
+    /// The break countdown is running.
+    InProgress,
+
+    /// The break lasted its full length.
+    Completed,
+
+    /// The break ended before its length had passed.
+    Skipped,
+
+    /// The break was postponed, the reminder is sent again at the given time.
+    Snoozed(Instant),
+
+    /// The break was abandoned without being taken.
+    Expired,
+}
+
+/// A change in a break's lifecycle.
+#[derive(Clone, Copy, Debug)]
+pub struct Transition {
+    /// The state the break moved to.
+    pub state: BreakState,
+
+    /// When the break moved to the state.
+    pub at: Instant,
+}
+
 /// Details about a break.
+#[derive(Clone, Debug)]
 pub struct Break {
     /// The id of the break.
     pub id: Uuid,
 
-    /// When the break started.
-    pub started: Instant,
+    /// How long the break is meant to last.
+    pub length: Duration,
+
+    /// The part of the length added to make up for missed breaks.
+    pub debt: Duration,
+
+    /// How long was worked before the break, if the break length scaled with it.
+    pub worked: Option<Duration>,
+
+    /// The pomodoro work period the break follows, if it completed one.
+    pub pomodoro_cycle: Option<u32>,
+
+    /// The current state of the break, only changed through validated transitions.
+    state: BreakState,
+
+    /// Every state the break has been in, oldest first.
+    transitions: Vec<Transition>,
+
+    /// How many times the break has been snoozed.
+    pub snoozes: u32,
+
+    /// The activity picked from the message catalogue for this break, if any.
+    pub activity: Option<String>,
+
+    /// The routine walked through during this break, if any.
+    pub routine: Option<BreakRoutine>,
+
+    /// The escalation steps reached while the reminder was left open.
+    pub escalations: Vec<Escalation>,
+
+    /// The number of escalations, shared with the reminder.
+    pub escalation_level: EscalationLevel,
+}
+
+impl BreakState {
+    /// Returns if a reminder has been sent for the break and it has not ended.
+    pub fn is_outstanding(&self) -> bool {
+        matches!(
+            self,
+            Self::Notified | Self::Acknowledged | Self::InProgress | Self::Snoozed(_)
+        )
+    }
+
+    /// Returns if the reminder for the break is currently shown.
+    pub fn is_shown(&self) -> bool {
+        matches!(self, Self::Notified | Self::Acknowledged | Self::InProgress)
+    }
+
+    /// Returns if the break has reached its outcome and can no longer change.
+    pub fn is_ended(&self) -> bool {
+        matches!(self, Self::Completed | Self::Skipped | Self::Expired)
+    }
+
+    /// Returns if a break can move from this state to `to`.
+    fn can_move_to(&self, to: BreakState) -> bool {
+        use BreakState::*;
 
-    /// If the break has finished, when it was finished.
-    pub finished: Option<Instant>,
+        match self {
+            // A break taken without a reminder, such as time away, starts straight away.
+            Scheduled => matches!(to, Notified | InProgress | Expired),
+
+            // The reminder can be closed before it is shown or started.
+            Notified => matches!(
+                to,
+                Acknowledged | InProgress | Completed | Skipped | Snoozed(_) | Expired
+            ),
+            Acknowledged => matches!(to, InProgress | Completed | Skipped | Snoozed(_) | Expired),
+            InProgress => matches!(to, Completed | Skipped | Snoozed(_)),
+            Snoozed(_) => matches!(to, Notified | Expired),
+            Completed | Skipped | Expired => false,
+        }
+    }
+
+    /// The name of the state without its data.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Scheduled => "Scheduled",
+            Self::Notified => "Notified",
+            Self::Acknowledged => "Acknowledged",
+            Self::InProgress => "In progress",
+            Self::Completed => "Completed",
+            Self::Skipped => "Skipped",
+            Self::Snoozed(_) => "Snoozed",
+            Self::Expired => "Expired",
+        }
+    }
 }
 
 impl Break {
-    /// Constructs a new break that starts now.
-    pub fn new() -> Self {
+    /// Constructs a new break of the given length, scheduled at `now`.
+    pub fn new(now: Instant, length: Duration) -> Self {
         Self {
             id: Uuid::new_v4(),
-            started: Instant::now(),
-            finished: None,
+            length,
+            debt: Duration::ZERO,
+            worked: None,
+            pomodoro_cycle: None,
+            state: BreakState::Scheduled,
+            transitions: vec![Transition {
+                state: BreakState::Scheduled,
+                at: now,
+            }],
+            snoozes: 0,
+            activity: None,
+            routine: None,
+            escalations: Vec::new(),
+            escalation_level: EscalationLevel::default(),
         }
     }
-}
 
-impl Default for Break {
-    fn default() -> Self {
+    /// Constructs a placeholder for the next break, its interval runs from `now`.
+    pub fn scheduled(now: Instant) -> Self {
         Self {
             id: Uuid::nil(),
-            started: Instant::now(),
-            finished: Some(Instant::now()),
+            ..Self::new(now, Duration::ZERO)
         }
     }
-}
 
-impl Display for Break {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let finished = match self.finished.as_ref() {
-            Some(finished) => format!("{} minutes ago", finished.elapsed().as_secs() / 60),
-            None => "No".to_string(),
+    /// Constructs a break taken without a reminder, such as time away from the computer.
+    pub fn taken_between(started: Instant, ended: Instant) -> Self {
+        let mut taken = Self::new(started, ended.saturating_duration_since(started));
+        taken.record(BreakState::InProgress, started);
+        taken.record(BreakState::Completed, ended);
+        taken
+    }
+
+    /// The current state of the break.
+    pub fn state(&self) -> BreakState {
+        self.state
+    }
+
+    /// Every state the break has been in, oldest first.
+    pub fn transitions(&self) -> &[Transition] {
+        &self.transitions
+    }
+
+    /// When the break last changed state.
+    pub fn changed(&self) -> Instant {
+        // A break is always constructed with its first transition.
+        self.transitions.last().unwrap().at
+    }
+
+    /// When the break last entered a state matching `state`.
+    fn entered(&self, state: fn(&BreakState) -> bool) -> Option<Instant> {
+        self.transitions
+            .iter()
+            .rev()
+            .find(|transition| state(&transition.state))
+            .map(|transition| transition.at)
+    }
+
+    /// When the reminder was last sent.
+    pub fn notified(&self) -> Option<Instant> {
+        self.entered(|state| *state == BreakState::Notified)
+    }
+
+    /// When the break started, the countdown start if the reminder reported one, otherwise when
+    /// the reminder was last sent.
+    pub fn started(&self) -> Instant {
+        self.entered(|state| *state == BreakState::InProgress)
+            .filter(|started| self.notified().is_none_or(|notified| *started >= notified))
+            .or_else(|| self.notified())
+            .unwrap_or_else(|| self.changed())
+    }
+
+    /// When the break reached its outcome, `None` until it has ended.
+    pub fn ended(&self) -> Option<Instant> {
+        self.state.is_ended().then(|| self.changed())
+    }
+
+    /// If the break is snoozed, when the reminder should be sent again.
+    pub fn snoozed_until(&self) -> Option<Instant> {
+        match self.state {
+            BreakState::Snoozed(until) => Some(until),
+            _ => None,
+        }
+    }
+
+    /// How long the break actually lasted, `None` until it has been completed or skipped.
+    pub fn taken(&self) -> Option<Duration> {
+        if !matches!(self.state, BreakState::Completed | BreakState::Skipped) {
+            return None;
+        }
+
+        Some(self.changed().saturating_duration_since(self.started()))
+    }
+
+    /// Moves the break to `to` at `at` if the lifecycle allows it.
+    fn transition(&mut self, to: BreakState, at: Instant) -> Result<(), Error> {
+        if !self.state.can_move_to(to) {
+            return Err(Error::InvalidTransition(self.state, to));
+        }
+
+        self.record(to, at);
+        Ok(())
+    }
+
+    /// Moves the break to `to` at `at` without checking the lifecycle.
+    fn record(&mut self, to: BreakState, at: Instant) {
+        self.state = to;
+        self.transitions.push(Transition { state: to, at });
+    }
+
+    /// Records the reminder being sent at `now`.
+    pub fn notify(&mut self, now: Instant) -> Result<(), Error> {
+        self.transition(BreakState::Notified, now)
+    }
+
+    /// Records the event loop showing the reminder at `now`.
+    pub fn acknowledge(&mut self, now: Instant) -> Result<(), Error> {
+        self.transition(BreakState::Acknowledged, now)
+    }
+
+    /// Records the break countdown starting at `now`.
+    pub fn start(&mut self, now: Instant) -> Result<(), Error> {
+        self.transition(BreakState::InProgress, now)
+    }
+
+    /// Records the break ending at `now`, it is completed if its length has passed and skipped
+    /// otherwise.
+    pub fn end(&mut self, now: Instant) -> Result<(), Error> {
+        let taken = now.saturating_duration_since(self.started());
+        let outcome = if taken >= self.length {
+            BreakState::Completed
+        } else {
+            BreakState::Skipped
         };
-        write!(
-            f,
-            "Break {{ id: {}, started: {} minutes ago, finished: {} }}",
+
+        self.transition(outcome, now)
+    }
+
+    /// Records the break being postponed at `now` until `until`.
+    pub fn snooze(&mut self, now: Instant, until: Instant) -> Result<(), Error> {
+        self.transition(BreakState::Snoozed(until), now)
+    }
+
+    /// Records the break being abandoned at `now`.
+    pub fn expire(&mut self, now: Instant) -> Result<(), Error> {
+        self.transition(BreakState::Expired, now)
+    }
+
+    /// Records an escalation step reached at `now` and lets the reminder know.
+    pub fn escalate(&mut self, step: EscalationStep, now: Instant) {
+        self.escalations.push(Escalation { step, at: now });
+        self.escalation_level.set(self.escalations.len());
+    }
+
+    /// Returns if the break ended before its length had passed.
+    pub fn was_cut_short(&self) -> bool {
+        self.state == BreakState::Skipped
+    }
+
+    /// Describes the break relative to `now`.
+    pub fn describe(&self, now: Instant) -> String {
+        let state = match self.state {
+            BreakState::Skipped => format!(
+                "Skipped after {} of {} seconds",
+                self.taken().unwrap_or_default().as_secs(),
+                self.length.as_secs()
+            ),
+            BreakState::Snoozed(until) => format!(
+                "Snoozed for {} more minutes",
+                until.saturating_duration_since(now).as_secs() / 60
+            ),
+            state => state.name().to_string(),
+        };
+        let routine = match self.routine.as_ref() {
+            Some(routine) => format!(
+                "\"{}\" {} of {} steps",
+                routine.name,
+                routine.progress.get().min(routine.steps),
+                routine.steps
+            ),
+            None => "None".to_string(),
+        };
+        let transitions: Vec<_> = self
+            .transitions
+            .iter()
+            .map(|transition| {
+                format!(
+                    "{} {} minutes ago",
+                    transition.state.name(),
+                    minutes_since(transition.at, now)
+                )
+            })
+            .collect();
+
+        format!(
+            "Break {{ id: {}, state: {}, snoozes: {}, escalations: {}, routine: {} }}\n  {}",
             self.id,
-            self.started.elapsed().as_secs() / 60,
-            finished
+            state,
+            self.snoozes,
+            self.escalations.len(),
+            routine,
+            transitions.join(" -> ")
         )
     }
 }
+
+/// Whole minutes between `earlier` and `now`.
+pub(crate) fn minutes_since(earlier: Instant, now: Instant) -> u64 {
+    now.saturating_duration_since(earlier).as_secs() / 60
+}
+
+/// Describes a length of time in whole minutes, or in seconds if it is under a minute.
+pub(crate) fn format_length(length: Duration) -> String {
+    match length.as_secs() {
+        1 => "1 second".to_string(),
+        seconds @ 0..60 => format!("{seconds} seconds"),
+        60..120 => "1 minute".to_string(),
+        seconds => format!("{} minutes", seconds / 60),
+    }
+}
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("Break cannot move from {} to {}", .0.name(), .1.name())]
+    InvalidTransition(BreakState, BreakState),
+}
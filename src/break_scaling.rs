@@ -0,0 +1,128 @@
+use std::time::Duration;
+
+use crate::config::{BreakScalingConfig, ScalingPoint};
+
+/// Scales a schedule's break length with how long was worked before it, so a long stretch of
+/// work gets a longer break.
+#[derive(Clone, Debug)]
+pub struct BreakScaling {
+    /// The break lengths for amounts of work, ordered by the work time.
+    pub points: Vec<ScalingPoint>,
+
+    /// The shortest a break can be.
+    pub min_length: Duration,
+
+    /// The longest a break can be.
+    pub max_length: Duration,
+}
+
+impl BreakScaling {
+    /// Constructs the scaling from its config.
+    pub fn new(config: &BreakScalingConfig) -> Self {
+        let mut points = config.points.clone();
+        points.sort_by_key(|point| point.worked);
+
+        Self {
+            points,
+            min_length: config.min_length,
+            max_length: config.max_length,
+        }
+    }
+
+    /// Returns the break length after working for `worked`, `None` without any points.
+    ///
+    /// Lengths between points are interpolated, beyond the first or last point the nearest two
+    /// points are extended, and the result is kept within the bounds.
+    pub fn length(&self, worked: Duration) -> Option<Duration> {
+        let length = match self.points[..] {
+            [] => return None,
+            [ref only] => only.length,
+            _ => {
+                // The segment that contains the work time, or the nearest one at either end.
+                let end = self
+                    .points
+                    .iter()
+                    .position(|point| point.worked > worked)
+                    .unwrap_or(self.points.len() - 1)
+                    .max(1);
+                interpolate(&self.points[end - 1], &self.points[end], worked)
+            }
+        };
+
+        Some(length.clamp(self.min_length, self.max_length))
+    }
+
+    /// Describes the scaling.
+    pub fn describe(&self) -> String {
+        let points: Vec<_> = self
+            .points
+            .iter()
+            .map(|point| {
+                format!(
+                    "{} minutes after {} minutes",
+                    point.length.as_secs() / 60,
+                    point.worked.as_secs() / 60
+                )
+            })
+            .collect();
+
+        format!(
+            "Break length scales with work time: {}, from {} to {} minutes",
+            points.join(", "),
+            self.min_length.as_secs() / 60,
+            self.max_length.as_secs() / 60
+        )
+    }
+}
+
+/// Returns the length at `worked` on the line through the two points.
+fn interpolate(from: &ScalingPoint, to: &ScalingPoint, worked: Duration) -> Duration {
+    let millis = |duration: Duration| duration.as_millis() as i128;
+
+    let span = millis(to.worked) - millis(from.worked);
+    if span == 0 {
+        return to.length;
+    }
+
+    let rise = millis(to.length) - millis(from.length);
+    let length = millis(from.length) + (millis(worked) - millis(from.worked)) * rise / span;
+
+    Duration::from_millis(length.max(0) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn break_scaling_interpolates_between_points_within_bounds() {
+        let point = |worked, length| ScalingPoint { worked, length };
+        let scaling = BreakScaling::new(&BreakScalingConfig {
+            points: vec![
+                point(120 * MINUTE, 24 * MINUTE),
+                point(20 * MINUTE, 4 * MINUTE),
+            ],
+            min_length: 3 * MINUTE,
+            max_length: 30 * MINUTE,
+        });
+
+        assert_eq!(scaling.length(70 * MINUTE), Some(14 * MINUTE));
+        assert_eq!(scaling.length(15 * MINUTE), Some(3 * MINUTE));
+        assert_eq!(scaling.length(140 * MINUTE), Some(28 * MINUTE));
+        assert_eq!(scaling.length(200 * MINUTE), Some(30 * MINUTE));
+
+        let single = BreakScaling::new(&BreakScalingConfig {
+            points: vec![point(20 * MINUTE, 4 * MINUTE)],
+            ..Default::default()
+        });
+        assert_eq!(single.length(200 * MINUTE), Some(4 * MINUTE));
+
+        let empty = BreakScaling::new(&BreakScalingConfig {
+            points: Vec::new(),
+            ..Default::default()
+        });
+        assert_eq!(empty.length(200 * MINUTE), None);
+    }
+}
@@ -0,0 +1,97 @@
+use std::time::Instant;
+
+use chrono::{DateTime, DurationRound, LocalResult, NaiveDateTime, TimeDelta, Utc};
+
+use crate::clock::Clock;
+
+/// The longest gap in local time a daylight saving or time zone change can cause.
+const MAX_GAP: TimeDelta = TimeDelta::hours(3);
+
+/// Resolves the local time to the moment it happens in the current time zone.
+///
+/// A local time that happens twice, as the clocks go back, resolves to its first occurrence. A
+/// local time that is skipped, as the clocks go forward, resolves to the end of the gap, the
+/// first moment the clock reads a later time.
+pub fn resolve(clock: &dyn Clock, local: NaiveDateTime) -> DateTime<Utc> {
+    match clock.resolve_local(local) {
+        LocalResult::Single(utc) => utc,
+        LocalResult::Ambiguous(earliest, _) => earliest,
+        LocalResult::None => end_of_gap(clock, local),
+    }
+}
+
+/// Resolves the local time to the first moment it happens at or after `after`, falling back to
+/// [`resolve`] if it only happened before then.
+pub fn resolve_after(
+    clock: &dyn Clock,
+    local: NaiveDateTime,
+    after: DateTime<Utc>,
+) -> DateTime<Utc> {
+    match clock.resolve_local(local) {
+        LocalResult::Ambiguous(earliest, latest) if earliest < after => latest,
+        _ => resolve(clock, local),
+    }
+}
+
+/// Returns the first moment after the gap that skips `local`.
+fn end_of_gap(clock: &dyn Clock, local: NaiveDateTime) -> DateTime<Utc> {
+    // Gaps start and end on whole minutes, so the first whole minute that exists ends it.
+    let gap_minute = local.duration_trunc(TimeDelta::minutes(1)).unwrap_or(local);
+
+    (1..=MAX_GAP.num_minutes())
+        .map(|minutes| gap_minute + TimeDelta::minutes(minutes))
+        .find_map(|later| match clock.resolve_local(later) {
+            LocalResult::Single(utc) | LocalResult::Ambiguous(utc, _) => Some(utc),
+            LocalResult::None => None,
+        })
+        // Not a gap any time zone has, treat the time as UTC rather than never reaching it.
+        .unwrap_or_else(|| local.and_utc())
+}
+
+/// Returns the monotonic time the local time happens at, see [`resolve`].
+pub fn instant_at(clock: &dyn Clock, local: NaiveDateTime) -> Instant {
+    instant_of(clock, resolve(clock, local))
+}
+
+/// Returns the monotonic time of the UTC time, which may be in the past.
+pub fn instant_of(clock: &dyn Clock, utc: DateTime<Utc>) -> Instant {
+    let now = clock.now();
+    let delta = utc - clock.utc_now();
+
+    match delta.to_std() {
+        Ok(ahead) => now + ahead,
+        Err(_) => {
+            let behind = (-delta).to_std().unwrap_or_default();
+            now.checked_sub(behind).unwrap_or(now)
+        }
+    }
+}
+
+/// Returns the UTC time of the monotonic time, which may be in the past.
+pub fn utc_of(clock: &dyn Clock, instant: Instant) -> DateTime<Utc> {
+    let now = clock.now();
+    let utc_now = clock.utc_now();
+
+    match instant.checked_duration_since(now) {
+        Some(ahead) => utc_now + TimeDelta::from_std(ahead).unwrap_or_default(),
+        None => utc_now - TimeDelta::from_std(now - instant).unwrap_or_default(),
+    }
+}
+
+/// Returns the local time in the current time zone at the monotonic time.
+pub fn local_at(clock: &dyn Clock, instant: Instant) -> NaiveDateTime {
+    clock.local_at(utc_of(clock, instant))
+}
+
+/// Returns the difference between local time and UTC in the current time zone.
+pub fn utc_offset(clock: &dyn Clock) -> TimeDelta {
+    let utc_now = clock.utc_now();
+    clock.local_at(utc_now) - utc_now.naive_utc()
+}
+
+/// Describes an offset from UTC, e.g. "+13:00".
+pub fn describe_offset(offset: TimeDelta) -> String {
+    let sign = if offset < TimeDelta::zero() { '-' } else { '+' };
+    let minutes = offset.num_minutes().abs();
+    format!("{sign}{:02}:{:02}", minutes / 60, minutes % 60)
+}
@@ -0,0 +1,170 @@
+use std::{collections::VecDeque, fs, io, path::Path};
+
+use serde::Deserialize;
+use thiserror::Error;
+
+const CATALOGUE_PATH: &str = "./break-messages.toml";
+
+/// The break activity messages, loaded from `break-messages.toml` next to the config file.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Catalogue {
+    /// How many of the most recent messages are not shown again while others are available.
+    pub avoid_repeats: usize,
+
+    /// The messages to pick from, schedules use their own message if there are none.
+    pub messages: Vec<CatalogueMessage>,
+}
+
+/// A break activity message.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CatalogueMessage {
+    /// The reminder text.
+    pub text: String,
+
+    /// How likely the message is to be picked relative to the others, 0 never picks it.
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+
+    /// Tags that schedules can filter the messages by.
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+fn default_weight() -> u32 {
+    1
+}
+
+impl Default for Catalogue {
+    fn default() -> Self {
+        Self {
+            avoid_repeats: 3,
+            messages: Vec::new(),
+        }
+    }
+}
+
+impl Catalogue {
+    /// Loads the catalogue file, falling back to an empty catalogue if it does not exist.
+    pub fn load() -> Result<Self, Error> {
+        let path = Path::new(CATALOGUE_PATH);
+
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(Error::Read(e)),
+        };
+
+        Ok(toml::from_str(&contents)?)
+    }
+}
+
+/// Picks messages from the catalogue, weighted and avoiding recent repeats.
+pub struct MessagePicker {
+    /// The catalogue to pick from.
+    catalogue: Catalogue,
+
+    /// The indices of the most recently picked messages, oldest first.
+    recent: VecDeque<usize>,
+
+    /// The random source for weighted picks.
+    rng: fastrand::Rng,
+}
+
+impl MessagePicker {
+    /// Constructs a picker for the catalogue.
+    pub fn new(catalogue: Catalogue) -> Self {
+        Self {
+            catalogue,
+            recent: VecDeque::new(),
+            rng: fastrand::Rng::new(),
+        }
+    }
+
+    /// Picks a message with one of the given tags, or any message if there are no tags. Returns
+    /// `None` if no message matches.
+    pub fn pick(&mut self, tags: &[String]) -> Option<String> {
+        let matching: Vec<_> = self
+            .catalogue
+            .messages
+            .iter()
+            .enumerate()
+            .filter(|(_, message)| message.weight > 0)
+            .filter(|(_, message)| {
+                tags.is_empty() || message.tags.iter().any(|tag| tags.contains(tag))
+            })
+            .collect();
+
+        let fresh: Vec<_> = matching
+            .iter()
+            .filter(|(index, _)| !self.recent.contains(index))
+            .copied()
+            .collect();
+
+        // With fewer matching messages than recent ones, repeats cannot be avoided.
+        let candidates = if fresh.is_empty() { matching } else { fresh };
+
+        // Summed as `u64` so that large weights cannot overflow.
+        let total: u64 = candidates
+            .iter()
+            .map(|(_, message)| u64::from(message.weight))
+            .sum();
+        if total == 0 {
+            return None;
+        }
+
+        let mut roll = self.rng.u64(0..total);
+        let (index, message) = candidates.into_iter().find(|(_, message)| {
+            let weight = u64::from(message.weight);
+            if roll < weight {
+                return true;
+            }
+            roll -= weight;
+            false
+        })?;
+
+        self.recent.push_back(index);
+        while self.recent.len() > self.catalogue.avoid_repeats {
+            self.recent.pop_front();
+        }
+
+        Some(message.text.clone())
+    }
+}
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("Failed to read message catalogue:\n{0}")]
+    Read(#[source] io::Error),
+
+    #[error("Failed to parse message catalogue:\n{0}")]
+    Parse(#[from] toml::de::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn large_weights_do_not_overflow() {
+        let catalogue = toml::from_str::<Catalogue>(
+            r#"
+            [[messages]]
+            text = "Look out of a window."
+            weight = 4294967295
+
+            [[messages]]
+            text = "Stretch your arms."
+            weight = 4294967295
+            "#,
+        )
+        .unwrap();
+
+        let mut picker = MessagePicker::new(catalogue);
+        for _ in 0..10 {
+            assert!(picker.pick(&[]).is_some());
+        }
+    }
+}
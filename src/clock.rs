@@ -0,0 +1,183 @@
+use std::time::Instant;
+
+use chrono::{DateTime, Local, LocalResult, NaiveDateTime, TimeZone, Utc};
+
+#[cfg(test)]
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+#[cfg(test)]
+use chrono::FixedOffset;
+
+/// A source of the current time, allows the notifier to be driven by a fake clock in tests.
+pub trait Clock: Send {
+    /// Returns the current monotonic time.
+    fn now(&self) -> Instant;
+
+    /// Returns the current local wall-clock time.
+    fn local_now(&self) -> NaiveDateTime;
+
+    /// Returns the current wall-clock time in UTC, unaffected by daylight saving changes.
+    fn utc_now(&self) -> DateTime<Utc>;
+
+    /// Returns the local time at the UTC time in the current time zone.
+    fn local_at(&self, utc: DateTime<Utc>) -> NaiveDateTime;
+
+    /// Returns the UTC times the local time happens at in the current time zone, none if the
+    /// clocks skip it and two if they go back over it.
+    fn resolve_local(&self, local: NaiveDateTime) -> LocalResult<DateTime<Utc>>;
+}
+
+/// The real system clock, in the system time zone.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn local_now(&self) -> NaiveDateTime {
+        Local::now().naive_local()
+    }
+
+    fn utc_now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+
+    fn local_at(&self, utc: DateTime<Utc>) -> NaiveDateTime {
+        utc.with_timezone(&Local).naive_local()
+    }
+
+    fn resolve_local(&self, local: NaiveDateTime) -> LocalResult<DateTime<Utc>> {
+        Local
+            .from_local_datetime(&local)
+            .map(|time| time.with_timezone(&Utc))
+    }
+}
+
+/// A clock that only moves when it is manually advanced, clones share the same time. The local
+/// time zone is UTC until its offset is changed.
+#[cfg(test)]
+#[derive(Clone, Debug)]
+pub struct FakeClock {
+    now: Arc<Mutex<FakeTime>>,
+}
+
+#[cfg(test)]
+#[derive(Debug)]
+struct FakeTime {
+    /// The monotonic time.
+    instant: Instant,
+
+    /// The wall-clock time.
+    utc: DateTime<Utc>,
+
+    /// When the local offset from UTC changes and what to, in order.
+    offsets: Vec<(DateTime<Utc>, FixedOffset)>,
+}
+
+#[cfg(test)]
+impl FakeTime {
+    /// The local offset from UTC at the UTC time.
+    fn offset_at(&self, utc: DateTime<Utc>) -> FixedOffset {
+        self.offsets
+            .iter()
+            .rev()
+            .find(|(from, _)| *from <= utc)
+            .map_or(FixedOffset::east_opt(0).unwrap(), |(_, offset)| *offset)
+    }
+}
+
+#[cfg(test)]
+impl Default for FakeClock {
+    /// Starts the clock at midnight on Monday 2024-01-01.
+    fn default() -> Self {
+        let local = chrono::NaiveDate::from_ymd_opt(2024, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        Self::at(local)
+    }
+}
+
+#[cfg(test)]
+impl FakeClock {
+    /// Constructs a clock that starts at the given local time.
+    pub fn at(local: NaiveDateTime) -> Self {
+        Self {
+            now: Arc::new(Mutex::new(FakeTime {
+                instant: Instant::now(),
+                utc: local.and_utc(),
+                offsets: Vec::new(),
+            })),
+        }
+    }
+
+    /// Moves the clock forward by `duration`.
+    pub fn advance(&self, duration: Duration) {
+        let mut now = self.now.lock().unwrap();
+        now.instant += duration;
+        now.utc += duration;
+    }
+
+    /// Moves only the wall clock, as happens across a suspend on platforms where the monotonic
+    /// clock stops, or when the system clock is changed.
+    pub fn advance_wall(&self, delta: chrono::TimeDelta) {
+        self.now.lock().unwrap().utc += delta;
+    }
+
+    /// Changes the local offset from UTC to `hours` at the local time `local`, as a daylight
+    /// saving or time zone change does.
+    pub fn change_offset(&self, local: NaiveDateTime, hours: i32) {
+        let mut now = self.now.lock().unwrap();
+        let from = (local - now.offset_at(local.and_utc())).and_utc();
+        now.offsets
+            .push((from, FixedOffset::east_opt(hours * 60 * 60).unwrap()));
+        now.offsets.sort_by_key(|(from, _)| *from);
+    }
+}
+
+#[cfg(test)]
+impl Clock for FakeClock {
+    fn now(&self) -> Instant {
+        self.now.lock().unwrap().instant
+    }
+
+    fn local_now(&self) -> NaiveDateTime {
+        self.local_at(self.utc_now())
+    }
+
+    fn utc_now(&self) -> DateTime<Utc> {
+        self.now.lock().unwrap().utc
+    }
+
+    fn local_at(&self, utc: DateTime<Utc>) -> NaiveDateTime {
+        let now = self.now.lock().unwrap();
+        utc.naive_utc() + now.offset_at(utc)
+    }
+
+    fn resolve_local(&self, local: NaiveDateTime) -> LocalResult<DateTime<Utc>> {
+        let now = self.now.lock().unwrap();
+        let offsets = std::iter::once(FixedOffset::east_opt(0).unwrap())
+            .chain(now.offsets.iter().map(|(_, offset)| *offset));
+
+        // A local time happens at each offset that is in effect at the UTC time it gives.
+        let mut times: Vec<_> = offsets
+            .filter_map(|offset| {
+                let utc = (local - offset).and_utc();
+                (now.offset_at(utc) == offset).then_some(utc)
+            })
+            .collect();
+        times.sort();
+        times.dedup();
+
+        match times[..] {
+            [] => LocalResult::None,
+            [utc] => LocalResult::Single(utc),
+            [earliest, .., latest] => LocalResult::Ambiguous(earliest, latest),
+        }
+    }
+}
@@ -0,0 +1,650 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use chrono::NaiveTime;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{
+    catalogue::{self, Catalogue},
+    curve::IntervalRange,
+    days_off::{self, DaysOff},
+    routine::{self, Routines},
+    trigger::Cron,
+    working_hours::WorkingHours,
+};
+
+const CONFIG_PATH: &str = "./break-reminder.toml";
+
+/// User configuration, loaded from `break-reminder.toml` next to the log file.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// The independent reminder schedules, the first is the primary schedule that the interval
+    /// argument and the "Set interval..." tray item apply to.
+    pub schedules: Vec<ScheduleConfig>,
+
+    /// The weekly windows reminders are sent in, reminders are sent at any time if not set.
+    pub working_hours: Option<WorkingHours>,
+
+    /// The days no reminders are sent on at all.
+    pub days_off: DaysOff,
+
+    /// An iCalendar file of more days off, e.g. exported public holidays.
+    pub holiday_calendar: Option<PathBuf>,
+
+    /// How long without input before the time away is counted as a break, a zero threshold
+    /// turns idle detection off.
+    #[serde(with = "humantime_serde")]
+    pub idle_threshold: Duration,
+
+    /// What happens to the schedules when a pause ends.
+    pub resume: ResumeBehaviour,
+
+    /// Forces a reminder after too long without a real break, even while paused.
+    pub work_cap: Option<WorkCapConfig>,
+
+    /// Notifies when the active time for the day goes over a budget.
+    pub daily_limit: Option<DailyLimitConfig>,
+
+    /// Keeps track of break time missed from skipped, abandoned or paused breaks and makes later
+    /// breaks longer to make up for it.
+    pub break_debt: Option<BreakDebtConfig>,
+
+    /// The break activity messages, loaded from their own file.
+    #[serde(skip)]
+    pub catalogue: Catalogue,
+
+    /// The guided break routines, loaded from their own file.
+    #[serde(skip)]
+    pub routines: Routines,
+}
+
+impl Config {
+    /// Loads the config file, falling back to the default config if it does not exist.
+    pub fn load() -> Result<Self, Error> {
+        let path = Path::new(CONFIG_PATH);
+
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                return Ok(Self {
+                    catalogue: Catalogue::load()?,
+                    routines: Routines::load()?,
+                    ..Self::default()
+                })
+            }
+            Err(e) => return Err(Error::Read(e)),
+        };
+
+        let mut config: Self = toml::from_str(&contents)?;
+        if config.schedules.is_empty() {
+            return Err(Error::NoSchedules);
+        }
+
+        if let Some(window) = config
+            .working_hours
+            .as_ref()
+            .and_then(WorkingHours::invalid_window)
+        {
+            return Err(Error::InvalidWorkingWindow(window.start, window.end));
+        }
+
+        if let Some(day) = config.days_off.invalid_day() {
+            return Err(Error::InvalidDayOff(day.name.clone()));
+        }
+
+        if let Some(path) = config.holiday_calendar.as_ref() {
+            let holidays = days_off::load_ics(path)?;
+            config.days_off.days.extend(holidays);
+        }
+
+        if let Some(schedule) = config.schedules.iter().find(|schedule| {
+            schedule
+                .adaptive
+                .as_ref()
+                .is_some_and(|adaptive| adaptive.min_interval > adaptive.max_interval)
+        }) {
+            return Err(Error::InvalidAdaptiveBounds(schedule.name.clone()));
+        }
+
+        if let Some(schedule) = config
+            .schedules
+            .iter()
+            .find(|schedule| !schedule.curve.iter().all(IntervalRange::is_valid))
+        {
+            return Err(Error::InvalidIntervalCurve(schedule.name.clone()));
+        }
+
+        // The ranges' intervals would replace the adjusted interval, so adapting could not work.
+        if let Some(schedule) = config
+            .schedules
+            .iter()
+            .find(|schedule| schedule.adaptive.is_some() && !schedule.curve.is_empty())
+        {
+            return Err(Error::AdaptiveIntervalCurve(schedule.name.clone()));
+        }
+
+        if let Some(schedule) = config.schedules.iter().find(|schedule| {
+            schedule
+                .break_scaling
+                .as_ref()
+                .is_some_and(|scaling| scaling.min_length > scaling.max_length)
+        }) {
+            return Err(Error::InvalidBreakScaling(schedule.name.clone()));
+        }
+
+        config.catalogue = Catalogue::load()?;
+        config.routines = Routines::load()?;
+
+        if let Some((schedule, routine)) = config.schedules.iter().find_map(|schedule| {
+            let routine = schedule.routine.as_ref()?;
+            config
+                .routines
+                .get(routine)
+                .is_none()
+                .then_some((schedule, routine))
+        }) {
+            return Err(Error::UnknownRoutine(
+                schedule.name.clone(),
+                routine.clone(),
+            ));
+        }
+
+        Ok(config)
+    }
+
+    /// Returns if any schedule runs as a pomodoro timer.
+    pub fn has_pomodoro(&self) -> bool {
+        self.schedules
+            .iter()
+            .any(|schedule| schedule.pomodoro.is_some())
+    }
+
+    /// Sets the interval of the primary schedule.
+    pub fn set_primary_interval(&mut self, interval: Duration) {
+        if let Some(schedule) = self.schedules.first_mut() {
+            schedule.interval = interval;
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            schedules: vec![ScheduleConfig::default()],
+            working_hours: None,
+            days_off: DaysOff::default(),
+            holiday_calendar: None,
+            idle_threshold: Duration::from_secs(60 * 5),
+            resume: ResumeBehaviour::default(),
+            work_cap: None,
+            daily_limit: None,
+            break_debt: None,
+            catalogue: Catalogue::default(),
+            routines: Routines::default(),
+        }
+    }
+}
+
+/// Configuration for the cap on continuous work.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WorkCapConfig {
+    /// The longest stretch of work without a real break before a reminder is forced.
+    #[serde(with = "humantime_serde")]
+    pub max_work: Duration,
+
+    /// The shortest break that ends a stretch of work, shorter breaks count as work.
+    #[serde(with = "humantime_serde")]
+    pub min_break: Duration,
+
+    /// How long after a forced reminder that did not end the stretch another is forced.
+    #[serde(with = "humantime_serde")]
+    pub repeat: Duration,
+
+    /// If reminders are forced while reminders are paused.
+    pub override_pause: bool,
+
+    /// If a snoozed break is sent again early once the cap is exceeded.
+    pub override_snooze: bool,
+}
+
+impl Default for WorkCapConfig {
+    fn default() -> Self {
+        Self {
+            max_work: Duration::from_secs(60 * 90),
+            min_break: Duration::from_secs(60 * 5),
+            repeat: Duration::from_secs(60 * 10),
+            override_pause: true,
+            override_snooze: true,
+        }
+    }
+}
+
+/// Configuration for the daily screen time budget.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DailyLimitConfig {
+    /// How much active time a day before the first notification.
+    #[serde(with = "humantime_serde")]
+    pub budget: Duration,
+
+    /// How long after the first notification the second is sent, each later gap is half of the
+    /// previous one.
+    #[serde(with = "humantime_serde")]
+    pub repeat: Duration,
+
+    /// The shortest gap between notifications.
+    #[serde(with = "humantime_serde")]
+    pub min_repeat: Duration,
+}
+
+impl DailyLimitConfig {
+    /// Returns how much active time the notification after `notified` earlier ones is sent at.
+    pub fn threshold(&self, notified: u32) -> Duration {
+        let mut threshold = self.budget;
+        let mut repeat = self.repeat;
+        for _ in 0..notified {
+            threshold += repeat.max(self.min_repeat);
+            repeat /= 2;
+        }
+
+        threshold
+    }
+}
+
+impl Default for DailyLimitConfig {
+    fn default() -> Self {
+        Self {
+            budget: Duration::from_secs(60 * 60 * 8),
+            repeat: Duration::from_secs(60 * 60),
+            min_repeat: Duration::from_secs(60 * 10),
+        }
+    }
+}
+
+/// Configuration for the break debt.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BreakDebtConfig {
+    /// The most break time that can be owed, anything missed beyond it is forgiven.
+    #[serde(with = "humantime_serde")]
+    pub max_debt: Duration,
+
+    /// The most a single break is made longer by, as a percentage of its length.
+    pub max_extension: u32,
+}
+
+impl Default for BreakDebtConfig {
+    fn default() -> Self {
+        Self {
+            max_debt: Duration::from_secs(60 * 30),
+            max_extension: 100,
+        }
+    }
+}
+
+/// What happens to the schedules when a pause ends, either early or when it runs out.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResumeBehaviour {
+    /// Any break that became due during the pause is sent straight away.
+    #[default]
+    RemindIfOverdue,
+
+    /// Every schedule starts a fresh interval.
+    RestartInterval,
+}
+
+/// Configuration for a single reminder schedule.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScheduleConfig {
+    /// The name of the schedule, shown in the reminder and debug info.
+    pub name: String,
+
+    /// The interval between a break ending and the next reminder, unused if the schedule has
+    /// fixed times.
+    #[serde(with = "humantime_serde", default = "default_interval")]
+    pub interval: Duration,
+
+    /// How long a break for this schedule lasts. When a break ends, every other schedule with a
+    /// break no longer than this one has its timer restarted.
+    #[serde(with = "humantime_serde", default = "default_break_length")]
+    pub break_length: Duration,
+
+    /// Makes the break length depend on how long was worked since the last break instead.
+    #[serde(default)]
+    pub break_scaling: Option<BreakScalingConfig>,
+
+    /// The reminder text, used when the message catalogue has no messages for the schedule.
+    #[serde(default = "default_message")]
+    pub message: String,
+
+    /// Only catalogue messages with one of these tags are shown, any message if empty.
+    #[serde(default)]
+    pub message_tags: Vec<String>,
+
+    /// Closes the reminder once the break is over so the next interval starts straight away.
+    #[serde(default = "default_auto_close")]
+    pub auto_close: bool,
+
+    /// Stops the reminder from being dismissed until the break is over, otherwise dismissing it
+    /// early records the break as cut short.
+    #[serde(default)]
+    pub enforce_break_length: bool,
+
+    /// How long snoozing the reminder postpones the break for.
+    #[serde(with = "humantime_serde", default = "default_snooze_length")]
+    pub snooze_length: Duration,
+
+    /// How many times a single break can be snoozed, 0 removes the snooze option.
+    #[serde(default = "default_max_snoozes")]
+    pub max_snoozes: u32,
+
+    /// The name of a routine from the routines file to walk through during each break.
+    #[serde(default)]
+    pub routine: Option<String>,
+
+    /// Makes a reminder that is left open more noticeable over time.
+    #[serde(default)]
+    pub escalation: Option<EscalationConfig>,
+
+    /// Adjusts the interval within bounds depending on how recent breaks were taken.
+    #[serde(default)]
+    pub adaptive: Option<AdaptiveConfig>,
+
+    /// Time of day ranges with their own interval, e.g. shorter in the afternoon. `interval` is
+    /// used outside of every range. Cannot be combined with `adaptive`.
+    #[serde(default)]
+    pub curve: Vec<IntervalRange>,
+
+    /// Sends reminders at wall-clock slots every interval instead of an interval after the last
+    /// break, e.g. at :00 and :30 for a 30 minute interval.
+    #[serde(default)]
+    pub alignment: Option<AlignmentConfig>,
+
+    /// Local times of day to send reminders at instead of every interval, e.g. `["12:30"]`.
+    #[serde(default)]
+    pub times: Vec<NaiveTime>,
+
+    /// A cron expression to send reminders at instead of every interval, e.g. `0 */2 * * 1-5`.
+    /// Reminders are sent at whichever of this and `times` comes first.
+    #[serde(default)]
+    pub cron: Option<Cron>,
+
+    /// Runs the schedule as a pomodoro timer, where the interval is the length of a work period
+    /// and breaks alternate between short and long.
+    #[serde(default)]
+    pub pomodoro: Option<PomodoroConfig>,
+}
+
+impl Default for ScheduleConfig {
+    fn default() -> Self {
+        Self {
+            name: "Break".to_string(),
+            interval: default_interval(),
+            break_length: default_break_length(),
+            break_scaling: None,
+            message: default_message(),
+            message_tags: Vec::new(),
+            auto_close: default_auto_close(),
+            enforce_break_length: false,
+            snooze_length: default_snooze_length(),
+            max_snoozes: default_max_snoozes(),
+            routine: None,
+            escalation: None,
+            adaptive: None,
+            curve: Vec::new(),
+            alignment: None,
+            times: Vec::new(),
+            cron: None,
+            pomodoro: None,
+        }
+    }
+}
+
+/// Configuration for escalating a reminder that is left open.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EscalationConfig {
+    /// How long the reminder is left open before each step.
+    #[serde(with = "humantime_serde")]
+    pub delay: Duration,
+
+    /// The steps to escalate through in order.
+    pub steps: Vec<EscalationStep>,
+}
+
+impl Default for EscalationConfig {
+    fn default() -> Self {
+        Self {
+            delay: Duration::from_secs(60 * 2),
+            steps: vec![
+                EscalationStep::Renotify,
+                EscalationStep::Sound,
+                EscalationStep::FullScreen,
+            ],
+        }
+    }
+}
+
+/// A way of making a reminder more noticeable.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EscalationStep {
+    /// Brings the reminder back to the front and flashes it.
+    Renotify,
+
+    /// Brings the reminder back to the front and plays a sound.
+    Sound,
+
+    /// Covers the screen behind the reminder.
+    FullScreen,
+}
+
+/// Configuration for adapting a schedule's interval to how breaks are taken.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AdaptiveConfig {
+    /// The shortest the interval can become.
+    #[serde(with = "humantime_serde")]
+    pub min_interval: Duration,
+
+    /// The longest the interval can become.
+    #[serde(with = "humantime_serde")]
+    pub max_interval: Duration,
+
+    /// How much a single adjustment changes the interval by.
+    #[serde(with = "humantime_serde")]
+    pub step: Duration,
+
+    /// How many ended breaks are looked at before adjusting.
+    pub history: usize,
+}
+
+impl Default for AdaptiveConfig {
+    fn default() -> Self {
+        Self {
+            min_interval: Duration::from_secs(60 * 10),
+            max_interval: Duration::from_secs(60 * 40),
+            step: Duration::from_secs(60 * 5),
+            history: 4,
+        }
+    }
+}
+
+/// Configuration for scaling a schedule's break length with the time worked before it.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BreakScalingConfig {
+    /// The break lengths for amounts of work, lengths between the points are interpolated and
+    /// lengths beyond them follow the nearest two points.
+    pub points: Vec<ScalingPoint>,
+
+    /// The shortest a break can be.
+    #[serde(with = "humantime_serde")]
+    pub min_length: Duration,
+
+    /// The longest a break can be.
+    #[serde(with = "humantime_serde")]
+    pub max_length: Duration,
+}
+
+impl Default for BreakScalingConfig {
+    fn default() -> Self {
+        Self {
+            points: vec![
+                ScalingPoint {
+                    worked: Duration::from_secs(60 * 25),
+                    length: Duration::from_secs(60 * 5),
+                },
+                ScalingPoint {
+                    worked: Duration::from_secs(60 * 60 * 3),
+                    length: Duration::from_secs(60 * 20),
+                },
+            ],
+            min_length: Duration::from_secs(60),
+            max_length: Duration::from_secs(60 * 30),
+        }
+    }
+}
+
+/// The break length after an amount of work.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScalingPoint {
+    /// How long was worked since the last break.
+    #[serde(with = "humantime_serde")]
+    pub worked: Duration,
+
+    /// How long the break is.
+    #[serde(with = "humantime_serde")]
+    pub length: Duration,
+}
+
+/// Configuration for aligning a schedule's reminders to the clock.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AlignmentConfig {
+    /// How far the slots are shifted from the hour, e.g. 5 minutes for :05 and :35.
+    #[serde(with = "humantime_serde")]
+    pub offset: Duration,
+
+    /// The shortest time between a break ending, or the app starting, and the next reminder.
+    /// Slots closer than this are skipped.
+    #[serde(with = "humantime_serde")]
+    pub min_gap: Duration,
+}
+
+impl Default for AlignmentConfig {
+    fn default() -> Self {
+        Self {
+            offset: Duration::ZERO,
+            min_gap: Duration::from_secs(60 * 10),
+        }
+    }
+}
+
+/// Configuration for a pomodoro schedule.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PomodoroConfig {
+    /// The length of the break after most work periods.
+    #[serde(with = "humantime_serde")]
+    pub short_break: Duration,
+
+    /// The length of the break after every `long_break_every` work periods.
+    #[serde(with = "humantime_serde")]
+    pub long_break: Duration,
+
+    /// How many work periods there are per long break.
+    pub long_break_every: u32,
+}
+
+impl Default for PomodoroConfig {
+    fn default() -> Self {
+        Self {
+            short_break: Duration::from_secs(60 * 5),
+            long_break: Duration::from_secs(60 * 15),
+            long_break_every: 4,
+        }
+    }
+}
+
+fn default_interval() -> Duration {
+    Duration::from_secs(60 * 20)
+}
+
+fn default_break_length() -> Duration {
+    Duration::from_secs(60 * 5)
+}
+
+fn default_snooze_length() -> Duration {
+    Duration::from_secs(60 * 5)
+}
+
+fn default_max_snoozes() -> u32 {
+    3
+}
+
+fn default_auto_close() -> bool {
+    true
+}
+
+fn default_message() -> String {
+    "Take a break, stand up, drink some water, stretch.".to_string()
+}
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("Failed to read config file:\n{0}")]
+    Read(#[source] io::Error),
+
+    #[error("Failed to parse config file:\n{0}")]
+    Parse(#[from] toml::de::Error),
+
+    #[error("Config file must contain at least one schedule")]
+    NoSchedules,
+
+    #[error("Working hours window from {0} to {1} must end after it starts")]
+    InvalidWorkingWindow(NaiveTime, NaiveTime),
+
+    #[error("Day off \"{0}\" must end on or after its start")]
+    InvalidDayOff(String),
+
+    #[error("Failed to load holiday calendar:\n{0}")]
+    Holidays(#[from] days_off::Error),
+
+    #[error(
+        "Adaptive interval for schedule \"{0}\" must have a minimum no longer than its maximum"
+    )]
+    InvalidAdaptiveBounds(String),
+
+    #[error(
+        "Interval curve for schedule \"{0}\" must have ranges with an interval that do not start and end at the same time"
+    )]
+    InvalidIntervalCurve(String),
+
+    #[error("Schedule \"{0}\" cannot have both an adaptive interval and an interval curve")]
+    AdaptiveIntervalCurve(String),
+
+    #[error(
+        "Break scaling for schedule \"{0}\" must have a minimum length no longer than its maximum"
+    )]
+    InvalidBreakScaling(String),
+
+    #[error("Failed to load message catalogue:\n{0}")]
+    Catalogue(#[from] catalogue::Error),
+
+    #[error("Failed to load break routines:\n{0}")]
+    Routines(#[from] routine::Error),
+
+    #[error("Schedule \"{0}\" uses routine \"{1}\" which is not in the routines file")]
+    UnknownRoutine(String, String),
+}
@@ -0,0 +1,157 @@
+use std::time::Duration;
+
+use chrono::{Days, NaiveDateTime, NaiveTime, TimeDelta};
+use serde::Deserialize;
+
+/// How many range boundaries an interval is followed across before giving up, only reached by
+/// intervals of weeks.
+const MAX_BOUNDARIES: usize = 1000;
+
+/// Changes a schedule's interval with the time of day, e.g. longer in the morning and shorter in
+/// the afternoon as fatigue sets in.
+#[derive(Clone, Debug)]
+pub struct IntervalCurve {
+    /// The time ranges with their own interval, the first that contains a time applies.
+    pub ranges: Vec<IntervalRange>,
+}
+
+/// A daily range of local time with its own interval.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IntervalRange {
+    /// The local time the range starts.
+    pub start: NaiveTime,
+
+    /// The local time the range ends, a range that ends before it starts runs over midnight.
+    pub end: NaiveTime,
+
+    /// The interval within the range.
+    #[serde(with = "humantime_serde")]
+    pub interval: Duration,
+}
+
+impl IntervalCurve {
+    /// Constructs a curve from the ranges, `None` if there are none.
+    pub fn new(ranges: &[IntervalRange]) -> Option<Self> {
+        if ranges.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            ranges: ranges.to_vec(),
+        })
+    }
+
+    /// Returns the interval at the local time, `default` outside of every range.
+    pub fn interval_at(&self, time: NaiveTime, default: Duration) -> Duration {
+        self.ranges
+            .iter()
+            .find(|range| range.contains(time))
+            .map_or(default, |range| range.interval)
+    }
+
+    /// Returns when an interval that started at the local time `from` is due.
+    ///
+    /// Each stretch of time counts for its share of the interval in effect then, so an interval
+    /// that crosses into another range is due once the shares add up to a whole interval, e.g.
+    /// 10 minutes of a 20 minute interval then 15 minutes of a 30 minute one.
+    pub fn next_due(&self, from: NaiveDateTime, default: Duration) -> NaiveDateTime {
+        let mut remaining = 1.0;
+        let mut at = from;
+
+        for _ in 0..MAX_BOUNDARIES {
+            let interval = self.interval_at(at.time(), default).as_secs_f64();
+            let due = at + seconds(remaining * interval);
+
+            match self.next_boundary(at) {
+                Some(boundary) if boundary < due => {
+                    let stretch = (boundary - at).num_milliseconds() as f64 / 1000.0;
+                    remaining -= stretch / interval;
+                    at = boundary;
+                }
+                _ => return due,
+            }
+        }
+
+        at + seconds(remaining * default.as_secs_f64())
+    }
+
+    /// Returns the first time after `after` a range starts or ends.
+    fn next_boundary(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        [after.date(), after.date() + Days::new(1)]
+            .into_iter()
+            .flat_map(|date| {
+                self.ranges
+                    .iter()
+                    .flat_map(move |range| [date.and_time(range.start), date.and_time(range.end)])
+            })
+            .filter(|boundary| *boundary > after)
+            .min()
+    }
+
+    /// Describes the interval of each range.
+    pub fn describe(&self) -> String {
+        let ranges: Vec<_> = self
+            .ranges
+            .iter()
+            .map(|range| {
+                format!(
+                    "{} minutes from {} to {}",
+                    range.interval.as_secs() / 60,
+                    range.start.format("%H:%M"),
+                    range.end.format("%H:%M")
+                )
+            })
+            .collect();
+
+        format!("Interval curve: {}", ranges.join(", "))
+    }
+}
+
+impl IntervalRange {
+    /// Returns if the range has an interval and does not start and end at the same time.
+    pub fn is_valid(&self) -> bool {
+        !self.interval.is_zero() && self.start != self.end
+    }
+
+    /// Returns if the local time is within the range.
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            self.start <= time || time < self.end
+        }
+    }
+}
+
+/// Converts fractional seconds to a time delta, to the nearest millisecond.
+fn seconds(seconds: f64) -> TimeDelta {
+    TimeDelta::milliseconds((seconds * 1000.0).round() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: Duration = Duration::from_secs(60 * 60);
+
+    fn range(start: u32, end: u32) -> IntervalRange {
+        IntervalRange {
+            start: NaiveTime::from_hms_opt(start, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(end, 0, 0).unwrap(),
+            interval: HOUR,
+        }
+    }
+
+    #[test]
+    fn interval_range_can_run_over_midnight() {
+        let night = range(22, 6);
+        let time = |hour| NaiveTime::from_hms_opt(hour, 0, 0).unwrap();
+
+        assert!(night.contains(time(23)));
+        assert!(night.contains(time(2)));
+        assert!(!night.contains(time(6)));
+        assert!(!night.contains(time(12)));
+        assert!(!range(9, 9).is_valid());
+    }
+}
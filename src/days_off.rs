@@ -0,0 +1,272 @@
+use std::{fs, io, path::Path};
+
+use chrono::{Datelike, Days, NaiveDate};
+use serde::Deserialize;
+use thiserror::Error;
+use tracing::info;
+
+/// The days reminders are not sent on at all, e.g. public holidays and vacations.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(transparent)]
+pub struct DaysOff {
+    pub days: Vec<DayOff>,
+}
+
+/// A single day off or a range of them.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DayOff {
+    /// What the day off is, shown as the reason no reminders are sent.
+    pub name: String,
+
+    /// The first day off.
+    pub start: NaiveDate,
+
+    /// The last day off, the same as `start` if not set.
+    #[serde(default)]
+    pub end: Option<NaiveDate>,
+
+    /// If the days off are on the same dates every year, the year of the dates is ignored.
+    #[serde(default)]
+    pub yearly: bool,
+}
+
+impl DaysOff {
+    /// Returns the day off that `date` is part of, if any.
+    pub fn get(&self, date: NaiveDate) -> Option<&DayOff> {
+        self.days.iter().find(|day| day.contains(date))
+    }
+
+    /// Returns the first day off that ends before it starts.
+    pub fn invalid_day(&self) -> Option<&DayOff> {
+        self.days
+            .iter()
+            .find(|day| !day.yearly && day.last() < day.start)
+    }
+}
+
+impl DayOff {
+    /// The last day off.
+    pub fn last(&self) -> NaiveDate {
+        self.end.unwrap_or(self.start)
+    }
+
+    /// Returns if `date` is one of the days off.
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        if !self.yearly {
+            return self.start <= date && date <= self.last();
+        }
+
+        let day_of_year = |date: NaiveDate| (date.month(), date.day());
+        let (start, end, day) = (
+            day_of_year(self.start),
+            day_of_year(self.last()),
+            day_of_year(date),
+        );
+
+        // A yearly range can run over the new year.
+        if start <= end {
+            start <= day && day <= end
+        } else {
+            start <= day || day <= end
+        }
+    }
+}
+
+/// Loads the all-day events of an iCalendar file as days off, e.g. an exported calendar of
+/// public holidays. Events that repeat yearly are yearly days off.
+pub fn load_ics(path: &Path) -> Result<Vec<DayOff>, Error> {
+    let contents = fs::read_to_string(path).map_err(Error::Read)?;
+    parse_ics(&contents)
+}
+
+/// Parses the all-day events of an iCalendar file as days off, timed events are ignored.
+pub fn parse_ics(contents: &str) -> Result<Vec<DayOff>, Error> {
+    // Long lines are folded onto following lines that start with whitespace.
+    let mut lines: Vec<String> = Vec::new();
+    for line in contents.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(folded), Some(last)) => last.push_str(folded),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let mut days = Vec::new();
+    let mut event: Option<IcsEvent> = None;
+
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        // Parameters such as `;VALUE=DATE` follow the property name.
+        let property = name.split(';').next().unwrap_or(name);
+
+        match (property, event.as_mut()) {
+            ("BEGIN", None) if value == "VEVENT" => event = Some(IcsEvent::default()),
+            ("END", Some(_)) if value == "VEVENT" => match event.take() {
+                Some(event) if event.timed => info!(
+                    "Ignoring timed event \"{}\" in the holiday calendar",
+                    event.summary.unwrap_or_default()
+                ),
+                Some(event) => days.push(event.into_day_off()?),
+                None => {}
+            },
+            ("SUMMARY", Some(event)) => event.summary = Some(unescape_text(value)),
+            // A date-time such as `20241225T090000Z` starts a timed event, not a day off.
+            ("DTSTART", Some(event)) if is_ics_date_time(value) => event.timed = true,
+            ("DTSTART", Some(event)) => event.start = Some(parse_ics_date(value)?),
+            ("DTEND", Some(event)) if !is_ics_date_time(value) => {
+                event.end = Some(parse_ics_date(value)?)
+            }
+            ("RRULE", Some(event)) => {
+                event.yearly = value.split(';').any(|rule| rule == "FREQ=YEARLY")
+            }
+            _ => {}
+        }
+    }
+
+    Ok(days)
+}
+
+/// The parts of an iCalendar event a day off is made from.
+#[derive(Default)]
+struct IcsEvent {
+    summary: Option<String>,
+    start: Option<NaiveDate>,
+    end: Option<NaiveDate>,
+    yearly: bool,
+    timed: bool,
+}
+
+impl IcsEvent {
+    /// Converts the event to a day off, the end date of an event is the day after it ends.
+    fn into_day_off(self) -> Result<DayOff, Error> {
+        let summary = self.summary.unwrap_or_else(|| "Holiday".to_string());
+        let Some(start) = self.start else {
+            return Err(Error::MissingStart(summary));
+        };
+
+        let end = self
+            .end
+            .and_then(|end| end.checked_sub_days(Days::new(1)))
+            .filter(|end| *end > start);
+
+        Ok(DayOff {
+            name: summary,
+            start,
+            end,
+            yearly: self.yearly,
+        })
+    }
+}
+
+/// Returns if an iCalendar value is a date-time rather than a date.
+fn is_ics_date_time(value: &str) -> bool {
+    value.contains('T')
+}
+
+/// Parses an iCalendar date value, e.g. `20241225`.
+fn parse_ics_date(value: &str) -> Result<NaiveDate, Error> {
+    if value.len() != 8 {
+        return Err(Error::InvalidDate(value.to_string()));
+    }
+    NaiveDate::parse_from_str(value, "%Y%m%d").map_err(|_| Error::InvalidDate(value.to_string()))
+}
+
+/// Removes the escaping from an iCalendar text value.
+fn unescape_text(value: &str) -> String {
+    let mut text = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n' | 'N') => text.push('\n'),
+            Some(escaped) => text.push(escaped),
+            None => {}
+        }
+    }
+    text
+}
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("Failed to read holiday calendar:\n{0}")]
+    Read(#[source] io::Error),
+
+    #[error("Holiday calendar has an invalid date \"{0}\"")]
+    InvalidDate(String),
+
+    #[error("Holiday calendar event \"{0}\" has no start date")]
+    MissingStart(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn holiday_calendar_events_are_days_off() {
+        let ics = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+BEGIN:VEVENT\r
+SUMMARY:New Year's Day\r
+DTSTART;VALUE=DATE:20240101\r
+DTEND;VALUE=DATE:20240102\r
+RRULE:FREQ=YEARLY\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+SUMMARY:Christmas\\, Boxing Day and a very long name that is folded onto the \r
+ next line\r
+DTSTART;VALUE=DATE:20241225\r
+DTEND;VALUE=DATE:20241227\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+SUMMARY:Dentist\r
+DTSTART:20240103T090000Z\r
+DTEND:20240103T100000Z\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+        let days = parse_ics(ics).unwrap();
+        assert_eq!(days.len(), 2);
+
+        assert_eq!(days[0].name, "New Year's Day");
+        assert_eq!(days[0].start, date(2024, 1, 1));
+        assert_eq!(days[0].end, None);
+        assert!(days[0].yearly);
+
+        assert_eq!(
+            days[1].name,
+            "Christmas, Boxing Day and a very long name that is folded onto the next line"
+        );
+        assert_eq!(days[1].start, date(2024, 12, 25));
+        assert_eq!(days[1].end, Some(date(2024, 12, 26)));
+        assert!(!days[1].yearly);
+
+        let holidays = DaysOff { days };
+        assert!(holidays.get(date(2025, 1, 1)).is_some());
+        assert!(holidays.get(date(2025, 12, 25)).is_none());
+
+        // The timed event does not make its day a day off.
+        assert!(holidays.get(date(2024, 1, 3)).is_none());
+    }
+
+    #[test]
+    fn holiday_calendar_event_needs_a_start() {
+        let ics = "BEGIN:VEVENT\nSUMMARY:Someday\nEND:VEVENT\n";
+        assert!(matches!(
+            parse_ics(ics),
+            Err(Error::MissingStart(name)) if name == "Someday"
+        ));
+    }
+}
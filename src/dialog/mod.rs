@@ -1,4 +1,5 @@
 pub mod number_input_dialog;
+pub mod text_input_dialog;
 
 use windows::Win32::UI::WindowsAndMessaging::{DLGITEMTEMPLATE, DLGTEMPLATE, IDCANCEL, IDH_OK};
 
@@ -38,6 +38,24 @@ impl<const T: usize, const I: usize, const S: usize> NumberInputDialogTemplate<T
     /// # Safety
     /// - `title`, `input_title`, and `submit_title` **must** be valid null-terminated utf-16.
     pub unsafe fn new(title: [u16; T], input_title: [u16; I], submit_title: [u16; S]) -> Self {
+        Self::with_input_style(
+            title,
+            input_title,
+            submit_title,
+            ES_NUMBER as u32 | ES_LEFT as u32,
+        )
+    }
+
+    /// Constructs the dialog with the given edit control styles for the input.
+    ///
+    /// # Safety
+    /// - `title`, `input_title`, and `submit_title` **must** be valid null-terminated utf-16.
+    pub unsafe fn with_input_style(
+        title: [u16; T],
+        input_title: [u16; I],
+        submit_title: [u16; S],
+        input_style: u32,
+    ) -> Self {
         let dialog = DialogTemplate {
             dialog_template: DLGTEMPLATE {
                 style: WS_VISIBLE.0
@@ -82,8 +100,7 @@ impl<const T: usize, const I: usize, const S: usize> NumberInputDialogTemplate<T
                     | WS_VISIBLE.0
                     | WS_TABSTOP.0
                     | WS_GROUP.0
-                    | ES_NUMBER as u32
-                    | ES_LEFT as u32,
+                    | input_style,
                 dwExtendedStyle: WS_EX_NOPARENTNOTIFY.0,
                 x: 8,
                 y: 11,
@@ -0,0 +1,85 @@
+use std::cell::Cell;
+
+use windows::Win32::{
+    Foundation::{HWND, LPARAM, WPARAM},
+    UI::WindowsAndMessaging::{
+        EndDialog, GetDlgItemTextW, ES_AUTOHSCROLL, ES_LEFT, WM_CLOSE, WM_COMMAND, WM_INITDIALOG,
+    },
+};
+
+use crate::dialog::{
+    number_input_dialog::NumberInputDialogTemplate, CANCEL_ID_ISIZE, INPUT_ID, OK_ID_ISIZE,
+};
+
+/// The longest text the dialog will return, in utf-16 units.
+const MAX_TEXT_LENGTH: usize = 256;
+
+/// The text input dialog has the same layout as the number input dialog, only the input accepts
+/// any text.
+pub type TextInputDialogTemplate<const T: usize, const I: usize, const S: usize> =
+    NumberInputDialogTemplate<T, I, S>;
+
+impl<const T: usize, const I: usize, const S: usize> TextInputDialogTemplate<T, I, S> {
+    /// # Safety
+    /// - `title`, `input_title`, and `submit_title` **must** be valid null-terminated utf-16.
+    pub unsafe fn new_text(title: [u16; T], input_title: [u16; I], submit_title: [u16; S]) -> Self {
+        Self::with_input_style(
+            title,
+            input_title,
+            submit_title,
+            ES_LEFT as u32 | ES_AUTOHSCROLL as u32,
+        )
+    }
+}
+
+/// Callback used by the text input, process events from the dialog.
+pub extern "system" fn text_input_dialog_callback(
+    hwnd: HWND,
+    message: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> isize {
+    // The lparam is a *mut String pointer to a String on the main thread, the same as the number
+    // input dialog it is only valid for the WM_INITDIALOG message so is captured in a thread local.
+    thread_local! {
+        pub static OUTPUT_PTR: Cell<Option<*mut String>> = const {Cell::new(None)};
+    }
+
+    unsafe {
+        match message {
+            WM_INITDIALOG => {
+                let text_ptr = lparam.0 as *mut String;
+                OUTPUT_PTR.set(Some(text_ptr));
+            }
+
+            WM_COMMAND => {
+                let message_id = wparam.0 as isize;
+
+                match message_id {
+                    // Unlike the number input, empty text is a valid response.
+                    1 | OK_ID_ISIZE => {
+                        let mut buffer = [0u16; MAX_TEXT_LENGTH];
+                        let length = GetDlgItemTextW(hwnd, INPUT_ID.into(), &mut buffer) as usize;
+                        let text = String::from_utf16_lossy(&buffer[..length]);
+
+                        if let Some(output_ptr) = OUTPUT_PTR.get() {
+                            *output_ptr = text;
+                        }
+
+                        EndDialog(hwnd, OK_ID_ISIZE).unwrap()
+                    }
+
+                    CANCEL_ID_ISIZE => EndDialog(hwnd, CANCEL_ID_ISIZE).unwrap(),
+
+                    _ => {}
+                }
+            }
+
+            WM_CLOSE => EndDialog(hwnd, CANCEL_ID_ISIZE).unwrap(),
+
+            _ => {}
+        }
+
+        0
+    }
+}
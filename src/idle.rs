@@ -0,0 +1,128 @@
+use std::time::Duration;
+
+#[cfg(test)]
+use std::{
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+#[cfg(test)]
+use crate::clock::{Clock, FakeClock};
+
+/// A source of how long the user has been away from the computer, allows idle detection to be
+/// faked in tests.
+pub trait IdleSource: Send {
+    /// Returns how long it has been since the last user input, `None` if it cannot be read.
+    fn idle_time(&self) -> Option<Duration>;
+}
+
+/// Reads the idle time from the operating system.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemIdleSource;
+
+#[cfg(windows)]
+impl IdleSource for SystemIdleSource {
+    fn idle_time(&self) -> Option<Duration> {
+        use windows::Win32::{
+            System::SystemInformation::GetTickCount,
+            UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO},
+        };
+
+        let mut info = LASTINPUTINFO {
+            cbSize: size_of::<LASTINPUTINFO>() as u32,
+            dwTime: 0,
+        };
+        if !unsafe { GetLastInputInfo(&mut info) }.as_bool() {
+            return None;
+        }
+
+        // Both are milliseconds since boot that wrap after ~49 days.
+        let now = unsafe { GetTickCount() };
+        Some(Duration::from_millis(now.wrapping_sub(info.dwTime) as u64))
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl IdleSource for SystemIdleSource {
+    /// Reads logind's idle hint for the current session, which desktop environments set once
+    /// the session has been idle for a while.
+    fn idle_time(&self) -> Option<Duration> {
+        use std::{
+            process::Command,
+            time::{SystemTime, UNIX_EPOCH},
+        };
+
+        let session = std::env::var("XDG_SESSION_ID").unwrap_or_else(|_| "auto".to_string());
+        let output = Command::new("loginctl")
+            .args([
+                "show-session",
+                &session,
+                "-p",
+                "IdleHint",
+                "-p",
+                "IdleSinceHint",
+            ])
+            .output()
+            .ok()?;
+        let output = String::from_utf8(output.stdout).ok()?;
+
+        let mut idle_hint = false;
+        let mut idle_since = None;
+        for line in output.lines() {
+            match line.split_once('=') {
+                Some(("IdleHint", value)) => idle_hint = value == "yes",
+                Some(("IdleSinceHint", value)) => idle_since = value.parse::<u64>().ok(),
+                _ => {}
+            }
+        }
+
+        if !idle_hint {
+            return Some(Duration::ZERO);
+        }
+
+        // The hint is in microseconds since the unix epoch.
+        let idle_since = UNIX_EPOCH + Duration::from_micros(idle_since?);
+        Some(
+            SystemTime::now()
+                .duration_since(idle_since)
+                .unwrap_or_default(),
+        )
+    }
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+impl IdleSource for SystemIdleSource {
+    fn idle_time(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// An idle source driven by a fake clock, the user is idle from the last call to `input`.
+#[cfg(test)]
+#[derive(Clone, Debug)]
+pub struct FakeIdleSource {
+    clock: FakeClock,
+    last_input: Arc<Mutex<Instant>>,
+}
+
+#[cfg(test)]
+impl FakeIdleSource {
+    /// Constructs an idle source with input at the clock's current time.
+    pub fn new(clock: FakeClock) -> Self {
+        let last_input = Arc::new(Mutex::new(clock.now()));
+        Self { clock, last_input }
+    }
+
+    /// Records user input at the clock's current time.
+    pub fn input(&self) {
+        *self.last_input.lock().unwrap() = self.clock.now();
+    }
+}
+
+#[cfg(test)]
+impl IdleSource for FakeIdleSource {
+    fn idle_time(&self) -> Option<Duration> {
+        let last_input = *self.last_input.lock().unwrap();
+        Some(self.clock.now().saturating_duration_since(last_input))
+    }
+}
@@ -1,32 +1,50 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")] // hide console window on Windows in release
 
-use std::{sync::mpsc::channel, time::Duration};
+use std::sync::mpsc::channel;
 
 use app::App;
+use config::Config;
 use logger::init_tracing;
-use message::Message;
+use message::{Message, NotifierEvent};
 use message_box::message_box;
 use notifier::Notifier;
 use only_instance::is_only_instance;
 use thiserror::Error;
 use tracing::{error, warn};
-use uuid::Uuid;
 use windows::Win32::UI::WindowsAndMessaging::{MB_ICONERROR, MB_ICONWARNING};
 use winit::{error::EventLoopError, event_loop::EventLoop};
 
 mod active_app;
+mod adaptive;
+mod alignment;
 mod app;
+mod break_debt;
 pub mod break_reminder;
+mod break_scaling;
+mod calendar;
+mod catalogue;
+mod clock;
+mod config;
+mod curve;
+mod days_off;
 pub mod dialog;
+mod idle;
 mod logger;
 pub mod message;
 pub mod message_box;
 mod notifier;
 mod only_instance;
 pub mod pause;
+mod pomodoro;
+mod routine;
+mod schedule;
+mod screen_time;
+mod trigger;
+mod working_hours;
 
 /// App to send a message box with a given interval after the previous message box has been interacted with.
-/// The interval is specified in minutes by the first command line argument and defaults to 20 minutes.
+/// Reminder schedules are loaded from `break-reminder.toml`, the interval of the primary schedule can
+/// be overridden in minutes by the first command line argument and defaults to 20 minutes.
 fn main() {
     let _log_guards = match init_tracing() {
         Ok(guards) => guards,
@@ -50,16 +68,19 @@ fn start_app() -> Result<(), Error> {
         return Ok(());
     };
 
+    let mut config = Config::load()?;
+    if let Some(interval) = Notifier::interval_from_args() {
+        config.set_primary_interval(interval);
+    }
+
     // Create event loop
-    let event_loop: EventLoop<Uuid> = EventLoop::with_user_event().build()?;
+    let event_loop: EventLoop<NotifierEvent> = EventLoop::with_user_event().build()?;
     event_loop.set_control_flow(winit::event_loop::ControlFlow::Wait);
     let proxy = event_loop.create_proxy();
 
     let (message_sender, message_receiver) = channel::<Message>();
-    let notifier_interval = Notifier::interval_from_args().unwrap_or(Duration::from_secs(60 * 20));
-
-    let notifier = Notifier::new(proxy, message_receiver, notifier_interval);
-    let mut app = App::new(message_sender);
+    let notifier = Notifier::new(proxy, message_receiver, &config);
+    let mut app = App::new(message_sender, config.has_pomodoro());
 
     notifier.start_event_loop();
     event_loop.run_app(&mut app)?;
@@ -73,6 +94,9 @@ pub enum Error {
     #[error("Failed to ensure only one instance:\n{0}")]
     OnlyInstance(#[from] only_instance::Error),
 
+    #[error("Failed to load config:\n{0}")]
+    Config(#[from] config::Error),
+
     #[error("Failed to build event loop:\n{0}")]
     EventLoop(#[from] EventLoopError),
 }
@@ -1,16 +1,31 @@
 use std::time::Duration;
 
+use chrono::NaiveDateTime;
 use uuid::Uuid;
 
+use crate::{break_reminder::Reminder, pause::PauseLength};
+
 /// Message variants between notifier and event loop.
 #[derive(Debug)]
 #[non_exhaustive]
 pub enum Message {
+    /// Message to let the notifier know the reminder for the break is shown.
+    AcknowledgeBreak(Uuid),
+
+    /// Message to let the notifier know the break countdown has started.
+    StartBreak(Uuid),
+
     /// Message to let the notifier know the break has ended.
     EndBreak(Uuid),
 
+    /// Message to postpone the break, the notifier sends the same break again after the snooze.
+    Snooze(Uuid),
+
     /// Message to make the notifier pause sending notificaitons for a given time.
-    PauseReminders(Duration),
+    PauseReminders(PauseLength),
+
+    /// Message to end the current pause early.
+    Resume,
 
     /// Message to print the current status of the notifier.
     PrintDebug,
@@ -20,4 +35,36 @@ pub enum Message {
 
     /// Message to set the interval of the notifier.
     SetInterval(Duration),
+
+    /// Message to start timing pomodoro work periods, with an optional task label.
+    StartPomodoro(Option<String>),
+
+    /// Message to skip the rest of the current pomodoro work period and start the break.
+    SkipPomodoro,
+
+    /// Message to stop the pomodoro timer and reset its cycle counter.
+    ResetPomodoro,
+}
+
+/// Event variants from the notifier to the event loop.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum NotifierEvent {
+    /// A break reminder to show.
+    Reminder(Box<Reminder>),
+
+    /// Reminders have been paused until the given local time, `None` until they are resumed.
+    Paused(Option<NaiveDateTime>),
+
+    /// The pause has ended and reminders will be sent again.
+    Resumed,
+
+    /// Today's screen time has reached another minute.
+    ScreenTime(Duration),
+
+    /// Today's screen time has gone over the daily budget.
+    DailyLimit { active: Duration, budget: Duration },
+
+    /// The reason reminders are held back has changed, `None` once they are no longer held back.
+    Suppressed(Option<String>),
 }
@@ -0,0 +1,98 @@
+use std::time::{Duration, Instant};
+
+use tracing::info;
+
+use crate::{
+    break_debt::{DebtChange, DebtReason},
+    break_reminder::{format_length, Break, BreakState},
+};
+
+use super::Notifier;
+
+impl Notifier {
+    /// Returns how much longer a break of `length` is made to make up for missed breaks.
+    pub(super) fn debt_extension(&self, length: Duration) -> Duration {
+        self.break_debt
+            .as_ref()
+            .map_or(Duration::ZERO, |debt| debt.extension(length))
+    }
+
+    /// Records how the ended break affects the break debt. Time missed from the break is owed,
+    /// the time it was made longer by is repaid as far as it was taken.
+    pub(super) fn record_break_debt(&mut self, schedule: &str, ended: &Break) {
+        let now = self.clock.now();
+        let Some(debt) = self.break_debt.as_mut() else {
+            return;
+        };
+
+        let missed = ended
+            .length
+            .saturating_sub(ended.taken().unwrap_or_default());
+        let reason = match ended.state() {
+            BreakState::Expired => DebtReason::Expired,
+            _ => DebtReason::Skipped,
+        };
+        let change = if missed > ended.debt {
+            debt.owe(schedule, reason, missed - ended.debt, now)
+        } else {
+            debt.repay(schedule, ended.debt - missed, now)
+        };
+
+        Self::log_debt_change(change);
+    }
+
+    /// Adds the breaks that came due during a pause to the break debt, must be called before
+    /// the schedules restart as the pause ends, as those breaks are then never sent.
+    pub(super) fn record_paused_debt(&mut self) {
+        let now = self.clock.now();
+        let Some(debt) = self.break_debt.as_mut() else {
+            return;
+        };
+
+        for schedule in &self.schedules {
+            if schedule.is_outstanding() || !schedule.is_due(&*self.clock) {
+                continue;
+            }
+
+            let missed = schedule.break_length_at(now);
+            Self::log_debt_change(debt.owe(&schedule.name, DebtReason::Paused, missed, now));
+        }
+    }
+
+    /// Restarts every schedule's interval from `started`, a snoozed break that is abandoned is
+    /// added to the break debt.
+    pub(super) fn restart_schedules(&mut self, started: Instant) {
+        for index in 0..self.schedules.len() {
+            self.restart_schedule(index, started);
+        }
+    }
+
+    /// Restarts the schedule's interval from `started`, a snoozed break that is abandoned is added
+    /// to the break debt.
+    pub(super) fn restart_schedule(&mut self, index: usize, started: Instant) {
+        let schedule = &mut self.schedules[index];
+        let name = schedule.name.clone();
+
+        if let Some(expired) = schedule.restart(started) {
+            self.record_break_debt(&name, &expired);
+        }
+    }
+
+    /// Logs a change to the break debt.
+    fn log_debt_change(change: Option<&DebtChange>) {
+        if let Some(change) = change {
+            info!(
+                "{} break was {}, {} of break time owed",
+                change.schedule,
+                change.reason.name(),
+                format_length(change.owed)
+            );
+        }
+    }
+
+    /// Describes the break debt, `None` if it is not kept.
+    pub(super) fn describe_break_debt(&self) -> Option<String> {
+        let debt = self.break_debt.as_ref()?;
+        Some(debt.describe(self.clock.now()))
+    }
+}
@@ -0,0 +1,55 @@
+use std::time::Instant;
+
+use chrono::{Days, NaiveTime};
+use tracing::info;
+
+use crate::{calendar, days_off::DayOff};
+
+use super::Notifier;
+
+impl Notifier {
+    /// Returns the day off today is part of, if any.
+    pub(super) fn day_off(&self) -> Option<&DayOff> {
+        self.days_off.get(self.clock.local_now().date())
+    }
+
+    /// Returns if today is a day off.
+    pub(super) fn is_day_off(&self) -> bool {
+        self.day_off().is_some()
+    }
+
+    /// Returns when today ends, `None` if today is not a day off.
+    pub(super) fn next_day_off_end(&self) -> Option<Instant> {
+        self.day_off()?;
+
+        let tomorrow = self.clock.local_now().date() + Days::new(1);
+        Some(calendar::instant_at(
+            &*self.clock,
+            tomorrow.and_time(NaiveTime::MIN),
+        ))
+    }
+
+    /// Restarts every schedule's interval when the days off have ended, so the first reminder
+    /// is a full interval after the day starts.
+    pub(super) fn update_day_off(&mut self) {
+        let day_off = self.day_off().map(|day| day.name.clone());
+        let was_day_off = std::mem::replace(&mut self.on_day_off, day_off.is_some());
+
+        match day_off {
+            Some(name) if !was_day_off => info!("Day off for {name}, holding back reminders"),
+
+            None if was_day_off => {
+                info!("Day off has ended, restarting reminders");
+
+                // The notifier may wake up after midnight, the interval still starts from then.
+                let now = self.clock.now();
+                let midnight = self.clock.local_now().date().and_time(NaiveTime::MIN);
+                let started = calendar::instant_at(&*self.clock, midnight).min(now);
+
+                self.restart_schedules(started);
+            }
+
+            _ => {}
+        }
+    }
+}
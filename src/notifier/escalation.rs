@@ -0,0 +1,30 @@
+use std::time::Instant;
+
+use tracing::info;
+
+use super::Notifier;
+
+impl Notifier {
+    /// Returns when the next open reminder should escalate.
+    pub(super) fn next_escalation(&self) -> Option<Instant> {
+        self.schedules
+            .iter()
+            .filter_map(|schedule| schedule.next_escalation())
+            .min()
+    }
+
+    /// Escalates every open reminder that has been left open long enough.
+    pub(super) fn escalate_reminders(&mut self) {
+        let now = self.clock.now();
+
+        for schedule in &mut self.schedules {
+            if let Some(step) = schedule.escalate(now) {
+                info!(
+                    "{} reminder was left open, escalating to {step:?} (step {})",
+                    schedule.name,
+                    schedule.last_break.escalations.len()
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use std::time::{Duration, Instant};
+
+use tracing::info;
+
+use crate::break_reminder::Break;
+
+use super::Notifier;
+
+/// How often the idle time is checked while the user is away, their return cannot be waited for.
+const AWAY_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+impl Notifier {
+    /// Returns when the idle time should next be checked, `None` if idle detection is off or
+    /// the idle time cannot be read. Without input the user is away once the threshold is
+    /// reached, so there is no need to check before then.
+    pub(super) fn next_idle_poll(&self) -> Option<Instant> {
+        if self.idle_threshold.is_zero() {
+            return None;
+        }
+
+        let idle_time = self.idle_source.idle_time()?;
+        let now = self.clock.now();
+        if self.away_since.is_some() {
+            return Some(now + AWAY_POLL_INTERVAL);
+        }
+
+        Some(now + self.idle_threshold.saturating_sub(idle_time))
+    }
+
+    /// Checks the idle time, reminders are held back while the user is away. When the user
+    /// returns, the time away is recorded as a natural break.
+    pub(super) fn update_idle(&mut self) {
+        if self.idle_threshold.is_zero() {
+            return;
+        }
+
+        let Some(idle_time) = self.idle_source.idle_time() else {
+            return;
+        };
+
+        let now = self.clock.now();
+        let last_input = now.checked_sub(idle_time).unwrap_or(now);
+
+        if idle_time >= self.idle_threshold {
+            self.away_since.get_or_insert(last_input);
+            return;
+        }
+
+        let Some(away_since) = self.away_since.take() else {
+            return;
+        };
+
+        let away = last_input.saturating_duration_since(away_since);
+        let natural_break = Break::taken_between(away_since, last_input);
+
+        info!(
+            "Away for {} minutes, counting it as a break",
+            away.as_secs() / 60
+        );
+        self.cover_break(&natural_break);
+    }
+}
@@ -0,0 +1,600 @@
+mod break_debt;
+mod days_off;
+mod escalation;
+mod idle;
+mod pause;
+mod pomodoro;
+mod screen_time;
+mod sleep;
+mod suppression;
+mod time_zone;
+mod work_cap;
+mod working_hours;
+
+use std::{
+    sync::mpsc::{Receiver, RecvTimeoutError},
+    thread::{self},
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, NaiveDateTime, TimeDelta, Utc};
+use tracing::{info, warn};
+use uuid::Uuid;
+use windows::Win32::UI::WindowsAndMessaging::MB_ICONINFORMATION;
+use winit::event_loop::{EventLoopClosed, EventLoopProxy};
+
+use crate::{
+    break_debt::BreakDebt,
+    break_reminder::{self, Break, Reminder},
+    calendar,
+    catalogue::MessagePicker,
+    clock::{Clock, SystemClock},
+    config::{Config, DailyLimitConfig, ResumeBehaviour, WorkCapConfig},
+    days_off::DaysOff,
+    idle::{IdleSource, SystemIdleSource},
+    message::{Message, NotifierEvent},
+    message_box::message_box,
+    pause::Pause,
+    schedule::Schedule,
+    screen_time::{FileStore, ScreenTime, ScreenTimeStore},
+    working_hours::WorkingHours,
+};
+
+use sleep::CLOCK_CHECK_INTERVAL;
+
+/// Something the notifier can send events to, normally the event loop.
+pub trait EventProxy: Send {
+    /// Sends an event, fails if the receiver has closed.
+    fn send_event(&self, event: NotifierEvent) -> Result<(), EventLoopClosed<NotifierEvent>>;
+}
+
+impl EventProxy for EventLoopProxy<NotifierEvent> {
+    fn send_event(&self, event: NotifierEvent) -> Result<(), EventLoopClosed<NotifierEvent>> {
+        EventLoopProxy::send_event(self, event)
+    }
+}
+
+/// The notifier object, operates on a separate thread to main event loop.
+pub struct Notifier {
+    /// Proxy to the event loop.
+    proxy: Box<dyn EventProxy>,
+
+    /// Receiever for messages from the event loop.
+    message_receiver: Receiver<Message>,
+
+    /// The source of the current time.
+    clock: Box<dyn Clock>,
+
+    /// The source of how long the user has been away.
+    idle_source: Box<dyn IdleSource>,
+
+    /// How long without input before the time away counts as a break, zero if idle detection
+    /// is off.
+    idle_threshold: Duration,
+
+    /// If the user is away, when their last input was.
+    away_since: Option<Instant>,
+
+    /// The monotonic and wall-clock times when the clocks were last compared.
+    last_seen: (Instant, DateTime<Utc>),
+
+    /// The local offset from UTC when the clocks were last compared.
+    utc_offset: TimeDelta,
+
+    /// The reminder schedules, the first is the primary schedule.
+    schedules: Vec<Schedule>,
+
+    /// Picks the activity for each new break from the message catalogue.
+    messages: MessagePicker,
+
+    /// The paused details of the notifier.
+    paused: Option<Pause>,
+
+    /// What happens to the schedules when a pause ends.
+    resume: ResumeBehaviour,
+
+    /// The weekly windows reminders are sent in, `None` to send reminders at any time.
+    working_hours: Option<WorkingHours>,
+
+    /// The start of the working window the notifier was last in.
+    working_window: Option<NaiveDateTime>,
+
+    /// The cap on continuous work, `None` if there is no cap.
+    work_cap: Option<WorkCapConfig>,
+
+    /// When the current stretch of work started, the end of the last real break.
+    working_since: Instant,
+
+    /// When a reminder was last forced for the current stretch of work.
+    work_cap_forced: Option<Instant>,
+
+    /// Where today's screen time is saved between runs.
+    screen_time_store: Box<dyn ScreenTimeStore>,
+
+    /// The active time accumulated today.
+    screen_time: ScreenTime,
+
+    /// When active time was last added to the screen time.
+    screen_time_counted: Instant,
+
+    /// The budget for the screen time, `None` if there is no limit.
+    daily_limit: Option<DailyLimitConfig>,
+
+    /// The days no reminders are sent on.
+    days_off: DaysOff,
+
+    /// If today was a day off when last checked.
+    on_day_off: bool,
+
+    /// Why reminders were held back when the event loop was last told.
+    suppressed: Option<String>,
+
+    /// The break time owed from missed breaks, `None` if it is not kept.
+    break_debt: Option<BreakDebt>,
+}
+
+struct ShouldCloseThread;
+
+impl Notifier {
+    pub fn new(
+        proxy: EventLoopProxy<NotifierEvent>,
+        message_receiver: Receiver<Message>,
+        config: &Config,
+    ) -> Self {
+        Self::with_sources(
+            proxy,
+            message_receiver,
+            config,
+            SystemClock,
+            SystemIdleSource,
+            FileStore,
+        )
+    }
+
+    /// Constructs a notifier that reads the time and idle time from the given sources, and keeps
+    /// the screen time in the given store.
+    pub fn with_sources(
+        proxy: impl EventProxy + 'static,
+        message_receiver: Receiver<Message>,
+        config: &Config,
+        clock: impl Clock + 'static,
+        idle_source: impl IdleSource + 'static,
+        screen_time_store: impl ScreenTimeStore + 'static,
+    ) -> Self {
+        let now = clock.now();
+        let utc_now = clock.utc_now();
+        let utc_offset = calendar::utc_offset(&clock);
+        let working_hours = config.working_hours.clone();
+        let working_window = working_hours
+            .as_ref()
+            .and_then(|hours| hours.window_start(clock.local_now()));
+        let schedules = config
+            .schedules
+            .iter()
+            .map(|schedule| Schedule::new(schedule, &config.routines, now))
+            .collect();
+        let screen_time = Self::load_screen_time(&screen_time_store, clock.local_now().date());
+        let on_day_off = config.days_off.get(clock.local_now().date()).is_some();
+
+        Self {
+            proxy: Box::new(proxy),
+            message_receiver,
+            clock: Box::new(clock),
+            idle_source: Box::new(idle_source),
+            idle_threshold: config.idle_threshold,
+            away_since: None,
+            last_seen: (now, utc_now),
+            utc_offset,
+            schedules,
+            messages: MessagePicker::new(config.catalogue.clone()),
+            paused: None,
+            resume: config.resume,
+            working_hours,
+            working_window,
+            work_cap: config.work_cap.clone(),
+            working_since: now,
+            work_cap_forced: None,
+            screen_time_store: Box::new(screen_time_store),
+            screen_time,
+            screen_time_counted: now,
+            daily_limit: config.daily_limit.clone(),
+            days_off: config.days_off.clone(),
+            on_day_off,
+            suppressed: None,
+            break_debt: config.break_debt.as_ref().map(BreakDebt::new),
+        }
+    }
+
+    /// Tries to load the interval from the program arguments.
+    pub fn interval_from_args() -> Option<Duration> {
+        let interval_str = std::env::args().next_back()?;
+        let interval_minutes: u64 = interval_str.parse().ok()?;
+        Some(Duration::from_secs(interval_minutes * 60))
+    }
+
+    /// This starts the event loop on another thread, takes ownership of the notifier.
+    /// The thread sleeps until either a message arrives or the next deadline passes, waking at
+    /// least every few minutes to check for the system having been asleep.
+    pub fn start_event_loop(mut self) {
+        thread::spawn(move || loop {
+            let timeout = self
+                .next_deadline()
+                .map(|deadline| deadline.saturating_duration_since(self.clock.now()))
+                .map_or(CLOCK_CHECK_INTERVAL, |timeout| {
+                    timeout.min(CLOCK_CHECK_INTERVAL)
+                });
+            let timeout = Some(timeout);
+
+            if self.step(timeout).is_err() {
+                return;
+            };
+        });
+    }
+
+    /// Waits up to `timeout` for a message and handles it, then sends a reminder if one is due.
+    /// A timeout of `None` waits until a message arrives.
+    fn step(&mut self, timeout: Option<Duration>) -> Result<(), ShouldCloseThread> {
+        let waited_from = self.clock.now();
+        let message = self.wait_for_message(timeout)?;
+        let asleep = self.detect_sleep(waited_from, timeout);
+        self.detect_time_zone_change()?;
+
+        if let Some(message) = message {
+            self.handle_message(message)?;
+        }
+
+        self.update_pause()?;
+        self.update_working_window();
+        self.update_day_off();
+        self.update_idle();
+        self.update_screen_time(asleep)?;
+        self.update_suppression()?;
+        self.escalate_reminders();
+
+        if self.enforce_work_cap()? {
+            return Ok(());
+        }
+
+        if let Some(index) = self.next_reminder() {
+            self.send_reminder(index)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns when the notifier next needs to wake up without a message, `None` if only a
+    /// message can change whether a reminder is due.
+    fn next_deadline(&self) -> Option<Instant> {
+        [
+            self.next_reminder_deadline(),
+            self.next_idle_poll(),
+            self.pause_end(),
+            self.next_escalation(),
+            self.next_forced_reminder(),
+            self.next_daily_limit(),
+        ]
+        .into_iter()
+        .flatten()
+        .min()
+    }
+
+    /// Returns when the next reminder could be sent, `None` if only a message can change whether
+    /// a reminder is due.
+    fn next_reminder_deadline(&self) -> Option<Instant> {
+        if self.is_day_off() {
+            return self.next_day_off_end();
+        }
+
+        if !self.is_working() {
+            return self.next_working_start();
+        }
+
+        let next_due = self
+            .schedules
+            .iter()
+            .filter(|schedule| !self.is_blocked(schedule))
+            .filter_map(|schedule| schedule.next_due(&*self.clock))
+            .min()?;
+
+        // While paused the pause end is the deadline, while away the idle poll is.
+        if self.is_paused() || self.away_since.is_some() {
+            return None;
+        }
+
+        Some(next_due)
+    }
+
+    /// Blocks until a message is received or the timeout passes.
+    fn wait_for_message(
+        &self,
+        timeout: Option<Duration>,
+    ) -> Result<Option<Message>, ShouldCloseThread> {
+        let result = match timeout {
+            Some(timeout) => self.message_receiver.recv_timeout(timeout),
+            None => self
+                .message_receiver
+                .recv()
+                .map_err(|_| RecvTimeoutError::Disconnected),
+        };
+
+        match result {
+            Ok(message) => Ok(Some(message)),
+            Err(RecvTimeoutError::Timeout) => Ok(None),
+            Err(RecvTimeoutError::Disconnected) => {
+                // The sender no longer exists, we should shut down.
+                warn!("Message sender has disconnected, shutting down notifier");
+                Err(ShouldCloseThread)
+            }
+        }
+    }
+
+    /// Handles a message from the event loop.
+    fn handle_message(&mut self, message: Message) -> Result<(), ShouldCloseThread> {
+        let now = self.clock.now();
+
+        match message {
+            Message::AcknowledgeBreak(uuid) => self.acknowledge_break(uuid, now),
+
+            Message::StartBreak(uuid) => self.start_break(uuid, now),
+
+            Message::EndBreak(uuid) => self.end_break(uuid, now),
+
+            Message::Snooze(uuid) => self.snooze_break(uuid, now),
+
+            Message::PauseReminders(length) => self.pause_reminders(length)?,
+
+            Message::Resume => self.resume_reminders()?,
+
+            Message::PrintDebug => {
+                match self.paused.as_ref() {
+                    Some(pause) => info!("{}", pause.describe(&*self.clock)),
+                    None => info!("Paused: No"),
+                };
+                info!("{}", self.describe_suppression());
+                info!("{}", self.describe_screen_time());
+                if let Some(debt) = self.describe_break_debt() {
+                    info!("{debt}");
+                }
+                for schedule in &self.schedules {
+                    info!("{}", schedule.describe(&*self.clock));
+                }
+            }
+
+            Message::ShowDebug => {
+                let pause = match self.paused.as_ref() {
+                    Some(pause) => pause.describe(&*self.clock),
+                    None => "Paused: No".to_string(),
+                };
+                let lines: Vec<_> = [
+                    pause,
+                    self.describe_suppression(),
+                    self.describe_screen_time(),
+                ]
+                .into_iter()
+                .chain(self.describe_break_debt())
+                .chain(
+                    self.schedules
+                        .iter()
+                        .map(|schedule| schedule.describe(&*self.clock)),
+                )
+                .collect();
+                let message = lines.join("\n");
+
+                message_box(message, MB_ICONINFORMATION);
+            }
+
+            Message::SetInterval(duration) => {
+                if let Some(schedule) = self.schedules.first_mut() {
+                    schedule.set_interval(duration);
+                }
+            }
+
+            Message::StartPomodoro(task) => self.start_pomodoro(task, now),
+
+            Message::SkipPomodoro => self.skip_pomodoro()?,
+
+            Message::ResetPomodoro => self.reset_pomodoro(),
+        }
+
+        Ok(())
+    }
+
+    /// Returns the schedule whose last break has the given id, preferring an outstanding break
+    /// over the copies other schedules keep of a finished one.
+    fn schedule_with_break(&mut self, id: Uuid) -> Option<&mut Schedule> {
+        let index = self
+            .schedules
+            .iter()
+            .position(|schedule| schedule.is_outstanding() && schedule.last_break.id == id)
+            .or_else(|| {
+                self.schedules
+                    .iter()
+                    .position(|schedule| schedule.last_break.id == id)
+            });
+
+        match index {
+            Some(index) => Some(&mut self.schedules[index]),
+            None => {
+                warn!("Break ID ({id}) does not match any break");
+                None
+            }
+        }
+    }
+
+    /// Moves the break with the given id through its lifecycle, returns the schedule it belongs
+    /// to if the transition was allowed.
+    fn transition_break(
+        &mut self,
+        id: Uuid,
+        transition: impl FnOnce(&mut Schedule) -> Result<(), break_reminder::Error>,
+    ) -> Option<&mut Schedule> {
+        let schedule = self.schedule_with_break(id)?;
+
+        if let Err(e) = transition(schedule) {
+            warn!("{} break ({id}) was not updated:\n{e}", schedule.name);
+            return None;
+        }
+
+        Some(schedule)
+    }
+
+    /// Records the event loop showing the reminder for the break with the given id.
+    fn acknowledge_break(&mut self, id: Uuid, now: Instant) {
+        self.transition_break(id, |schedule| schedule.last_break.acknowledge(now));
+    }
+
+    /// Records the countdown starting for the break with the given id.
+    fn start_break(&mut self, id: Uuid, now: Instant) {
+        self.transition_break(id, |schedule| schedule.last_break.start(now));
+    }
+
+    /// Ends the outstanding break with the given id, a break that ends early is recorded as
+    /// skipped.
+    fn end_break(&mut self, id: Uuid, now: Instant) {
+        let Some(ended) = self.transition_break(id, |schedule| schedule.last_break.end(now)) else {
+            return;
+        };
+
+        ended.adapt(now);
+        let ended_name = ended.name.clone();
+        let ended_break = ended.last_break.clone();
+
+        if let Some(routine) = ended_break.routine.as_ref() {
+            info!(
+                "{} break walked through \"{}\", {} of {} steps completed",
+                ended.name,
+                routine.name,
+                routine.progress.get().min(routine.steps),
+                routine.steps
+            );
+        }
+
+        if ended_break.was_cut_short() {
+            info!(
+                "{} break was cut short after {} of {} seconds",
+                ended.name,
+                ended_break.taken().unwrap_or_default().as_secs(),
+                ended_break.length.as_secs()
+            );
+        }
+
+        self.record_break_debt(&ended_name, &ended_break);
+        self.cover_break(&ended_break);
+    }
+
+    /// Every schedule without an outstanding break whose break is no longer than the time taken
+    /// for the finished break counts it as their own and restarts their timer. Schedules with
+    /// fixed times are never covered, they remind at their times regardless.
+    fn cover_break(&mut self, finished: &Break) {
+        self.record_real_break(finished);
+        let now = self.clock.now();
+        let taken = finished.taken().unwrap_or_default();
+
+        for schedule in &mut self.schedules {
+            if !schedule.is_outstanding()
+                && schedule.trigger.is_none()
+                && schedule.break_length_at(now) <= taken
+            {
+                schedule.last_break = finished.clone();
+            }
+        }
+    }
+
+    /// Snoozes the outstanding break with the given id so the same break is sent again once the
+    /// snooze ends. A break that has reached its snooze limit is sent again straight away.
+    fn snooze_break(&mut self, id: Uuid, now: Instant) {
+        self.transition_break(id, |schedule| {
+            if schedule.snooze(now)? {
+                return Ok(());
+            }
+
+            warn!(
+                "{} break has reached its limit of {} snoozes",
+                schedule.name, schedule.max_snoozes
+            );
+            schedule.last_break.snooze(now, now)
+        });
+    }
+
+    /// Returns the index of the schedule that should send a reminder now, if any. When several
+    /// are due only the one with the longest break is sent, as it covers the shorter ones.
+    fn next_reminder(&self) -> Option<usize> {
+        if !self.should_notify() {
+            return None;
+        }
+
+        let now = self.clock.now();
+        self.schedules
+            .iter()
+            .enumerate()
+            .filter(|(_, schedule)| schedule.is_due(&*self.clock) && !self.is_blocked(schedule))
+            .max_by_key(|(index, schedule)| {
+                (schedule.break_length_at(now), std::cmp::Reverse(*index))
+            })
+            .map(|(index, _)| index)
+    }
+
+    /// Returns if the schedule must wait because another break at least as long is outstanding.
+    fn is_blocked(&self, schedule: &Schedule) -> bool {
+        let length = schedule.break_length_at(self.clock.now());
+        self.schedules.iter().any(|other| {
+            !std::ptr::eq(other, schedule)
+                && other.is_outstanding()
+                && other.last_break.length >= length
+        })
+    }
+
+    /// Returns if reminders can be sent, they are held back on days off, while paused, outside
+    /// of working hours, or while the user is away.
+    fn should_notify(&self) -> bool {
+        self.suppression_reason().is_none()
+    }
+
+    /// Starts a break for the schedule at `index`, or resumes its snoozed break, and sends its
+    /// reminder to the event loop.
+    fn send_reminder(&mut self, index: usize) -> Result<(), ShouldCloseThread> {
+        match self.prepare_reminder(index, false) {
+            Some(reminder) => self.send_reminder_event(reminder),
+            None => Ok(()),
+        }
+    }
+
+    /// Starts a break for the schedule at `index`, or resumes its snoozed break, and returns its
+    /// reminder. A forced break is sent before the schedule's break is due.
+    fn prepare_reminder(&mut self, index: usize, forced: bool) -> Option<Reminder> {
+        let now = self.clock.now();
+        let debt = self.debt_extension(self.schedules[index].break_length_at(now));
+        let schedule = &mut self.schedules[index];
+        if !schedule.is_snoozed() {
+            let activity = self.messages.pick(&schedule.message_tags);
+            return Some(schedule.start_break(now, activity, debt, forced));
+        }
+
+        match schedule.resume_break(now) {
+            Ok(reminder) => Some(reminder),
+            Err(e) => {
+                warn!("{} break could not be sent again:\n{e}", schedule.name);
+                None
+            }
+        }
+    }
+
+    /// Sends a reminder to the event loop.
+    fn send_reminder_event(&self, reminder: Reminder) -> Result<(), ShouldCloseThread> {
+        self.send_event(NotifierEvent::Reminder(Box::new(reminder)))
+    }
+
+    /// Sends an event to the event loop.
+    fn send_event(&self, event: NotifierEvent) -> Result<(), ShouldCloseThread> {
+        let send_result = self.proxy.send_event(event);
+        if send_result.is_err() {
+            warn!("Event loop has closed, notifer will shut down");
+            return Err(ShouldCloseThread);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests;
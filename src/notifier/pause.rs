@@ -0,0 +1,117 @@
+use std::time::Instant;
+
+use chrono::{Days, NaiveTime, TimeDelta};
+use tracing::{info, warn};
+
+use crate::{
+    calendar,
+    config::ResumeBehaviour,
+    message::NotifierEvent,
+    pause::{describe_resume_time, Pause, PauseEnd, PauseLength},
+};
+
+use super::{Notifier, ShouldCloseThread};
+
+/// When "tomorrow morning" is without working hours.
+const MORNING: NaiveTime = NaiveTime::from_hms_opt(8, 0, 0).unwrap();
+
+impl Notifier {
+    /// Pauses reminders and lets the event loop know when they resume.
+    pub(super) fn pause_reminders(&mut self, length: PauseLength) -> Result<(), ShouldCloseThread> {
+        let pause = Pause {
+            started: self.clock.utc_now(),
+            resumes: self.resume_time(length),
+        };
+        let resumes = pause.resumes_local(&*self.clock);
+
+        info!("Pausing reminders until {}", describe_resume_time(resumes));
+        self.paused = Some(pause);
+
+        self.send_event(NotifierEvent::Paused(resumes))
+    }
+
+    /// Returns when a pause of `length` started now ends.
+    fn resume_time(&self, length: PauseLength) -> Option<PauseEnd> {
+        let local = self.clock.local_now();
+
+        match length {
+            PauseLength::For(duration) => Some(PauseEnd::At(self.clock.utc_now() + duration)),
+
+            PauseLength::Until(time) => {
+                // The time may still be ahead today as the clocks go back, even if it has been
+                // passed once.
+                let today = local.date().and_time(time);
+                let utc_now = self.clock.utc_now();
+                if calendar::resolve_after(&*self.clock, today, utc_now) > utc_now {
+                    Some(PauseEnd::Local(today))
+                } else {
+                    Some(PauseEnd::Local(today + Days::new(1)))
+                }
+            }
+
+            PauseLength::TomorrowMorning => {
+                let tomorrow = local.date() + Days::new(1);
+                // Just before midnight so a window that starts at midnight tomorrow counts.
+                let end_of_today = tomorrow.and_time(NaiveTime::MIN) - TimeDelta::nanoseconds(1);
+                let working_start = self
+                    .working_hours
+                    .as_ref()
+                    .and_then(|hours| hours.next_start(end_of_today));
+
+                Some(PauseEnd::Local(
+                    working_start.unwrap_or(tomorrow.and_time(MORNING)),
+                ))
+            }
+
+            PauseLength::UntilResumed => None,
+        }
+    }
+
+    /// Ends the pause once it has passed.
+    pub(super) fn update_pause(&mut self) -> Result<(), ShouldCloseThread> {
+        if self.paused.is_none() || self.is_paused() {
+            return Ok(());
+        }
+
+        info!("Pause has ended, resuming reminders");
+        self.end_pause()
+    }
+
+    /// Ends the current pause early.
+    pub(super) fn resume_reminders(&mut self) -> Result<(), ShouldCloseThread> {
+        if !self.is_paused() {
+            warn!("Received a resume message but reminders are not paused");
+            return Ok(());
+        }
+
+        info!("Resuming reminders early");
+        self.end_pause()
+    }
+
+    /// Clears the pause, restarts the schedules if configured to, and lets the event loop know.
+    fn end_pause(&mut self) -> Result<(), ShouldCloseThread> {
+        self.paused = None;
+
+        if self.resume == ResumeBehaviour::RestartInterval {
+            self.record_paused_debt();
+
+            self.restart_schedules(self.clock.now());
+        }
+
+        self.send_event(NotifierEvent::Resumed)
+    }
+
+    /// Returns if the notifier is paused.
+    pub(super) fn is_paused(&self) -> bool {
+        let Some(pause) = self.paused.as_ref() else {
+            return false;
+        };
+        pause.is_active(&*self.clock)
+    }
+
+    /// Returns when the current pause ends, `None` if not paused or paused until resumed.
+    pub(super) fn pause_end(&self) -> Option<Instant> {
+        let resumes = self.paused.as_ref()?.resumes_at(&*self.clock)?;
+        Some(calendar::instant_of(&*self.clock, resumes))
+    }
+}
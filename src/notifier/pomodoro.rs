@@ -0,0 +1,64 @@
+use std::time::Instant;
+
+use tracing::warn;
+
+use super::{Notifier, ShouldCloseThread};
+
+impl Notifier {
+    /// Returns the index of the first pomodoro schedule.
+    fn pomodoro_index(&self) -> Option<usize> {
+        let index = self
+            .schedules
+            .iter()
+            .position(|schedule| schedule.pomodoro.is_some());
+
+        if index.is_none() {
+            warn!("Received a pomodoro message but no schedule is a pomodoro");
+        }
+
+        index
+    }
+
+    /// Starts a new work period at `now` with an optional task label.
+    pub(super) fn start_pomodoro(&mut self, task: Option<String>, now: Instant) {
+        let Some(index) = self.pomodoro_index() else {
+            return;
+        };
+
+        self.restart_schedule(index, now);
+        if let Some(pomodoro) = self.schedules[index].pomodoro.as_mut() {
+            pomodoro.start(task);
+        }
+    }
+
+    /// Skips the rest of the current work period and starts the break straight away.
+    pub(super) fn skip_pomodoro(&mut self) -> Result<(), ShouldCloseThread> {
+        let Some(index) = self.pomodoro_index() else {
+            return Ok(());
+        };
+
+        let schedule = &self.schedules[index];
+        let is_running = schedule
+            .pomodoro
+            .as_ref()
+            .is_some_and(|pomodoro| pomodoro.running);
+
+        if !is_running || schedule.is_outstanding() {
+            warn!("Can only skip a running pomodoro work period");
+            return Ok(());
+        }
+
+        self.send_reminder(index)
+    }
+
+    /// Stops the pomodoro timer and resets its cycle counter.
+    pub(super) fn reset_pomodoro(&mut self) {
+        let Some(index) = self.pomodoro_index() else {
+            return;
+        };
+
+        if let Some(pomodoro) = self.schedules[index].pomodoro.as_mut() {
+            pomodoro.reset();
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use std::time::{Duration, Instant};
+
+use chrono::{NaiveDate, NaiveTime};
+use tracing::{info, warn};
+
+use crate::{
+    message::NotifierEvent,
+    screen_time::{format_hours, ScreenTime, ScreenTimeStore},
+};
+
+use super::{Notifier, ShouldCloseThread};
+
+impl Notifier {
+    /// Loads the screen time saved for today, starting a new day if it was saved on another day
+    /// or could not be loaded.
+    pub(super) fn load_screen_time(store: &dyn ScreenTimeStore, today: NaiveDate) -> ScreenTime {
+        match store.load() {
+            Ok(Some(screen_time)) if screen_time.date == today => screen_time,
+            Ok(_) => ScreenTime::new(today),
+            Err(e) => {
+                warn!("Starting screen time from zero:\n{e}");
+                ScreenTime::new(today)
+            }
+        }
+    }
+
+    /// Returns when the next daily limit notification is due if the user stays active, `None`
+    /// if there is no limit, the user is away, or it is a day off.
+    pub(super) fn next_daily_limit(&self) -> Option<Instant> {
+        let limit = self.daily_limit.as_ref()?;
+        if self.away_since.is_some() || self.is_day_off() {
+            return None;
+        }
+
+        let remaining = limit
+            .threshold(self.screen_time.notified)
+            .saturating_sub(self.screen_time.active);
+        Some(self.screen_time_counted + remaining)
+    }
+
+    /// Adds the time since the last update to today's screen time, unless the user is away.
+    /// `asleep` is the part of that time the system was asleep. The total is saved and sent to
+    /// the event loop whenever it reaches another minute.
+    pub(super) fn update_screen_time(&mut self, asleep: Duration) -> Result<(), ShouldCloseThread> {
+        let now = self.clock.now();
+        let local_now = self.clock.local_now();
+        let today = local_now.date();
+        let since = std::mem::replace(&mut self.screen_time_counted, now);
+
+        let mut active = match self.away_since {
+            Some(_) => Duration::ZERO,
+            None => now.saturating_duration_since(since).saturating_sub(asleep),
+        };
+
+        // Only the time since midnight counts towards a new day.
+        if today != self.screen_time.date {
+            let since_midnight = local_now - today.and_time(NaiveTime::MIN);
+            active = active.min(since_midnight.to_std().unwrap_or_default());
+        }
+
+        let last_date = self.screen_time.date;
+        let last_minutes = self.screen_time.active.as_secs() / 60;
+        self.screen_time.add(today, active);
+
+        if self.screen_time.date != last_date {
+            info!("New day, screen time starts from zero");
+        } else if self.screen_time.active.as_secs() / 60 == last_minutes {
+            return self.notify_daily_limit();
+        }
+
+        self.save_screen_time();
+        self.send_event(NotifierEvent::ScreenTime(self.screen_time.active))?;
+        self.notify_daily_limit()
+    }
+
+    /// Sends a notification once today's screen time passes the next threshold of the daily limit.
+    fn notify_daily_limit(&mut self) -> Result<(), ShouldCloseThread> {
+        let Some(limit) = self.daily_limit.as_ref() else {
+            return Ok(());
+        };
+
+        if self.away_since.is_some()
+            || self.is_day_off()
+            || self.screen_time.active < limit.threshold(self.screen_time.notified)
+        {
+            return Ok(());
+        }
+
+        let budget = limit.budget;
+        let active = self.screen_time.active;
+        self.screen_time.notified += 1;
+
+        info!(
+            "Screen time of {} is over the daily budget of {}",
+            format_hours(active),
+            format_hours(budget)
+        );
+        self.save_screen_time();
+
+        self.send_event(NotifierEvent::DailyLimit { active, budget })
+    }
+
+    /// Saves today's screen time so it survives a restart.
+    fn save_screen_time(&self) {
+        if let Err(e) = self.screen_time_store.save(&self.screen_time) {
+            warn!("{e}");
+        }
+    }
+
+    /// Describes today's screen time against the daily budget.
+    pub(super) fn describe_screen_time(&self) -> String {
+        let budget = self.daily_limit.as_ref().map(|limit| limit.budget);
+        self.screen_time.describe(budget)
+    }
+}
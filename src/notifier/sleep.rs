@@ -0,0 +1,74 @@
+use std::time::{Duration, Instant};
+
+use chrono::TimeDelta;
+use tracing::{info, warn};
+
+use crate::break_reminder::Break;
+
+use super::Notifier;
+
+/// How far the clocks can disagree before it is treated as a sleep or clock change.
+const CLOCK_GAP_THRESHOLD: Duration = Duration::from_secs(60);
+
+/// The longest the notifier waits before comparing the clocks, as a wait measured by a monotonic
+/// clock that stops while suspended ends late by the time asleep.
+pub(super) const CLOCK_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 5);
+
+impl Notifier {
+    /// Compares the monotonic and wall clocks since the last check to find time the system was
+    /// asleep, which is recorded as a completed break. `waited_from` and `timeout` are the start
+    /// and length of the last wait for a message. Returns how much of the time the monotonic
+    /// clock counted since the last check was spent asleep.
+    ///
+    /// Whether the monotonic clock keeps counting while suspended depends on the platform. If it
+    /// stops, the wall clock runs ahead of it, a forward change of the system clock looks the same
+    /// and is treated as a sleep. If it keeps counting, the wait for a message overruns its
+    /// timeout.
+    pub(super) fn detect_sleep(
+        &mut self,
+        waited_from: Instant,
+        timeout: Option<Duration>,
+    ) -> Duration {
+        let now = self.clock.now();
+        let utc_now = self.clock.utc_now();
+        let (last_now, last_utc) = std::mem::replace(&mut self.last_seen, (now, utc_now));
+
+        // Positive if the wall clock moved further than the monotonic clock.
+        let monotonic_gap = now.saturating_duration_since(last_now);
+        let drift = (utc_now - last_utc) - TimeDelta::from_std(monotonic_gap).unwrap_or_default();
+
+        if -drift >= TimeDelta::from_std(CLOCK_GAP_THRESHOLD).unwrap_or_default() {
+            warn!(
+                "System clock moved back by {} minutes",
+                -drift.num_minutes()
+            );
+        }
+        let asleep = drift.to_std().ok();
+
+        let overran = timeout
+            .map(|timeout| {
+                now.saturating_duration_since(waited_from)
+                    .saturating_sub(timeout)
+            })
+            .filter(|gap| *gap >= CLOCK_GAP_THRESHOLD);
+
+        let Some(asleep) = [asleep, overran]
+            .into_iter()
+            .flatten()
+            .max()
+            .filter(|gap| *gap >= CLOCK_GAP_THRESHOLD)
+        else {
+            return Duration::ZERO;
+        };
+
+        info!(
+            "System was asleep for {} minutes, counting it as a break",
+            asleep.as_secs() / 60
+        );
+
+        let sleep_break = Break::taken_between(now.checked_sub(asleep).unwrap_or(now), now);
+        self.cover_break(&sleep_break);
+
+        overran.unwrap_or_default()
+    }
+}
@@ -0,0 +1,49 @@
+use crate::{message::NotifierEvent, pause::describe_resume_time};
+
+use super::{Notifier, ShouldCloseThread};
+
+impl Notifier {
+    /// Returns why reminders are being held back, `None` if they are not.
+    pub(super) fn suppression_reason(&self) -> Option<String> {
+        if let Some(day) = self.day_off() {
+            return Some(format!("Day off: {}", day.name));
+        }
+
+        if self.is_paused() {
+            let resumes = self
+                .paused
+                .as_ref()
+                .and_then(|pause| pause.resumes_local(&*self.clock));
+            return Some(format!("Paused until {}", describe_resume_time(resumes)));
+        }
+
+        if !self.is_working() {
+            return Some("Outside working hours".to_string());
+        }
+
+        if self.away_since.is_some() {
+            return Some("Away from the computer".to_string());
+        }
+
+        None
+    }
+
+    /// Lets the event loop know when the reason reminders are held back changes.
+    pub(super) fn update_suppression(&mut self) -> Result<(), ShouldCloseThread> {
+        let reason = self.suppression_reason();
+        if reason == self.suppressed {
+            return Ok(());
+        }
+
+        self.suppressed.clone_from(&reason);
+        self.send_event(NotifierEvent::Suppressed(reason))
+    }
+
+    /// Describes why reminders are being held back.
+    pub(super) fn describe_suppression(&self) -> String {
+        match self.suppression_reason() {
+            Some(reason) => format!("Reminders suppressed: {reason}"),
+            None => "Reminders suppressed: No".to_string(),
+        }
+    }
+}
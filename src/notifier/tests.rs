@@ -0,0 +1,2423 @@
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+use chrono::{Datelike, NaiveDate, NaiveTime, Weekday};
+
+use super::*;
+use crate::{
+    break_debt::DebtReason,
+    break_reminder::{format_length, BreakState, Reminder},
+    catalogue::Catalogue,
+    clock::FakeClock,
+    config::{
+        AdaptiveConfig, AlignmentConfig, BreakDebtConfig, BreakScalingConfig, EscalationConfig,
+        EscalationStep, PomodoroConfig, ResumeBehaviour, ScheduleConfig,
+    },
+    curve::IntervalRange,
+    days_off::DayOff,
+    idle::FakeIdleSource,
+    pause::PauseLength,
+    routine::Routines,
+    screen_time::MemoryStore,
+};
+
+const MINUTE: Duration = Duration::from_secs(60);
+
+/// A day of January 2024, where the 1st is a Monday.
+fn date(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 1, day).unwrap()
+}
+
+fn time(hour: u32, minute: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+}
+
+/// The config the harness runs the schedules with, without idle detection. Tests set their
+/// feature's settings on top of it.
+fn base_config(schedules: Vec<ScheduleConfig>) -> Config {
+    Config {
+        schedules,
+        idle_threshold: Duration::ZERO,
+        ..Default::default()
+    }
+}
+
+impl EventProxy for Sender<NotifierEvent> {
+    fn send_event(&self, event: NotifierEvent) -> Result<(), EventLoopClosed<NotifierEvent>> {
+        self.send(event).map_err(|e| EventLoopClosed(e.0))
+    }
+}
+
+struct Harness {
+    notifier: Notifier,
+    clock: FakeClock,
+    idle: FakeIdleSource,
+    screen_time: MemoryStore,
+    messages: Sender<Message>,
+    events: Receiver<NotifierEvent>,
+
+    /// The screen time updates that have been sent.
+    screen_times: Vec<Duration>,
+
+    /// The reasons reminders are held back that have been sent.
+    suppressed: Vec<Option<String>>,
+
+    /// The events other than reminders, screen time updates, and suppression reasons that have
+    /// been sent.
+    other_events: Vec<NotifierEvent>,
+}
+
+impl Harness {
+    fn new(interval: Duration) -> Self {
+        Self::with_schedules(vec![schedule("Break", interval, 5 * MINUTE)])
+    }
+
+    fn with_schedules(schedules: Vec<ScheduleConfig>) -> Self {
+        Self::with_config(&base_config(schedules), FakeClock::default())
+    }
+
+    fn with_config(config: &Config, clock: FakeClock) -> Self {
+        Self::with_store(config, clock, MemoryStore::default())
+    }
+
+    /// Constructs a harness that keeps the screen time in an existing store.
+    fn with_store(config: &Config, clock: FakeClock, screen_time: MemoryStore) -> Self {
+        let (messages, message_receiver) = channel();
+        let (event_sender, events) = channel();
+        let idle = FakeIdleSource::new(clock.clone());
+        let notifier = Notifier::with_sources(
+            event_sender,
+            message_receiver,
+            config,
+            clock.clone(),
+            idle.clone(),
+            screen_time.clone(),
+        );
+
+        Self {
+            notifier,
+            clock,
+            idle,
+            screen_time,
+            messages,
+            events,
+            screen_times: Vec::new(),
+            suppressed: Vec::new(),
+            other_events: Vec::new(),
+        }
+    }
+
+    /// Sends a message and lets the notifier handle it.
+    fn send(&mut self, message: Message) {
+        self.messages.send(message).unwrap();
+        self.step();
+    }
+
+    fn step(&mut self) {
+        assert!(self.notifier.step(Some(Duration::ZERO)).is_ok());
+    }
+
+    /// Advances the clock one minute at a time, stepping the notifier after each minute.
+    /// Returns the reminders sent in that time.
+    fn run_for(&mut self, duration: Duration) -> Vec<Reminder> {
+        let mut elapsed = Duration::ZERO;
+        while elapsed < duration {
+            self.clock.advance(MINUTE);
+            elapsed += MINUTE;
+            self.step();
+        }
+        self.take_reminders()
+    }
+
+    /// Like `run_for`, but with user input every minute.
+    fn work_for(&mut self, duration: Duration) -> Vec<Reminder> {
+        let mut elapsed = Duration::ZERO;
+        while elapsed < duration {
+            self.clock.advance(MINUTE);
+            self.idle.input();
+            elapsed += MINUTE;
+            self.step();
+        }
+        self.take_reminders()
+    }
+
+    /// Returns the reminders sent since the last call, keeping any other events.
+    fn take_reminders(&mut self) -> Vec<Reminder> {
+        let mut reminders = Vec::new();
+        for event in self.events.try_iter() {
+            match event {
+                NotifierEvent::Reminder(reminder) => reminders.push(*reminder),
+                NotifierEvent::ScreenTime(active) => self.screen_times.push(active),
+                NotifierEvent::Suppressed(reason) => self.suppressed.push(reason),
+                event => self.other_events.push(event),
+            }
+        }
+        reminders
+    }
+
+    /// Acknowledges the outstanding break.
+    fn end_break(&mut self, id: Uuid) {
+        self.send(Message::EndBreak(id));
+    }
+}
+
+fn schedule(name: &str, interval: Duration, break_length: Duration) -> ScheduleConfig {
+    ScheduleConfig {
+        name: name.to_string(),
+        interval,
+        break_length,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn does_not_notify_before_interval() {
+    let mut harness = Harness::new(20 * MINUTE);
+
+    assert!(harness.run_for(19 * MINUTE).is_empty());
+    assert!(harness.notifier.next_reminder().is_none());
+}
+
+#[test]
+fn notifies_once_per_interval() {
+    let mut harness = Harness::new(20 * MINUTE);
+
+    let reminders = harness.run_for(20 * MINUTE);
+    assert_eq!(reminders.len(), 1);
+    assert_eq!(
+        reminders[0].break_id,
+        harness.notifier.schedules[0].last_break.id
+    );
+}
+
+#[test]
+fn waits_for_break_to_end_before_next_interval() {
+    let mut harness = Harness::new(20 * MINUTE);
+
+    let reminders = harness.run_for(20 * MINUTE);
+    assert_eq!(reminders.len(), 1);
+
+    // An unacknowledged break never triggers another reminder.
+    assert!(harness.run_for(3 * 60 * MINUTE).is_empty());
+
+    harness.end_break(reminders[0].break_id);
+    assert!(harness.run_for(19 * MINUTE).is_empty());
+    assert_eq!(harness.run_for(MINUTE).len(), 1);
+}
+
+#[test]
+fn mismatched_end_break_is_ignored() {
+    let mut harness = Harness::new(20 * MINUTE);
+
+    assert_eq!(harness.run_for(20 * MINUTE).len(), 1);
+    harness.end_break(Uuid::new_v4());
+
+    assert!(harness.notifier.schedules[0].is_outstanding());
+    assert!(harness.run_for(60 * MINUTE).is_empty());
+}
+
+#[test]
+fn reminds_regularly_over_a_working_day() {
+    let mut harness = Harness::new(20 * MINUTE);
+
+    let mut count = 0;
+    for _ in 0..8 * 60 {
+        for reminder in harness.run_for(MINUTE) {
+            count += 1;
+            harness.end_break(reminder.break_id);
+        }
+    }
+
+    assert_eq!(count, 8 * 3);
+}
+
+#[test]
+fn pause_suppresses_reminders_until_it_ends() {
+    let mut harness = Harness::new(20 * MINUTE);
+
+    harness.send(Message::PauseReminders(PauseLength::For(90 * MINUTE)));
+    assert!(harness.run_for(89 * MINUTE).is_empty());
+    assert!(harness.notifier.is_paused());
+
+    // The interval has long passed, so the reminder is sent as soon as the pause ends.
+    assert_eq!(harness.run_for(MINUTE).len(), 1);
+    assert!(!harness.notifier.is_paused());
+}
+
+#[test]
+fn set_interval_applies_to_current_break() {
+    let mut harness = Harness::new(20 * MINUTE);
+
+    assert!(harness.run_for(10 * MINUTE).is_empty());
+    harness.send(Message::SetInterval(60 * MINUTE));
+    assert!(harness.run_for(49 * MINUTE).is_empty());
+    assert_eq!(harness.run_for(MINUTE).len(), 1);
+}
+
+#[test]
+fn shortened_interval_notifies_immediately_when_overdue() {
+    let mut harness = Harness::new(60 * MINUTE);
+
+    assert!(harness.run_for(30 * MINUTE).is_empty());
+    harness.send(Message::SetInterval(15 * MINUTE));
+    assert_eq!(harness.take_reminders().len(), 1);
+}
+
+#[test]
+fn no_deadline_while_break_is_outstanding() {
+    let mut harness = Harness::new(20 * MINUTE);
+    let start = harness.clock.now();
+
+    assert_eq!(harness.notifier.next_deadline(), Some(start + 20 * MINUTE));
+
+    assert_eq!(harness.run_for(20 * MINUTE).len(), 1);
+    assert_eq!(harness.notifier.next_deadline(), None);
+}
+
+#[test]
+fn deadline_follows_pause_and_interval() {
+    let mut harness = Harness::new(20 * MINUTE);
+    let start = harness.clock.now();
+
+    harness.send(Message::PauseReminders(PauseLength::For(60 * MINUTE)));
+    assert_eq!(harness.notifier.next_deadline(), Some(start + 60 * MINUTE));
+
+    // The notifier wakes at the end of a pause even if no reminder is due yet.
+    harness.send(Message::PauseReminders(PauseLength::For(5 * MINUTE)));
+    assert_eq!(harness.notifier.next_deadline(), Some(start + 5 * MINUTE));
+
+    harness.run_for(5 * MINUTE);
+    assert_eq!(harness.notifier.next_deadline(), Some(start + 20 * MINUTE));
+
+    harness.send(Message::SetInterval(40 * MINUTE));
+    assert_eq!(harness.notifier.next_deadline(), Some(start + 40 * MINUTE));
+}
+
+#[test]
+fn pause_until_time_of_day() {
+    // Monday 10:00.
+    let mut harness = Harness::new(20 * MINUTE);
+    harness.clock.advance(10 * 60 * MINUTE);
+
+    harness.send(Message::PauseReminders(PauseLength::Until(time(14, 0))));
+    assert_eq!(
+        harness.notifier.next_deadline(),
+        Some(harness.clock.now() + 4 * 60 * MINUTE)
+    );
+    let pause = harness.notifier.paused.as_ref().unwrap();
+    assert!(pause.describe(&harness.clock).contains("until: Mon 14:00"));
+
+    // A time that has already passed today pauses until tomorrow.
+    harness.send(Message::PauseReminders(PauseLength::Until(time(9, 0))));
+    assert_eq!(
+        harness.notifier.next_deadline(),
+        Some(harness.clock.now() + 23 * 60 * MINUTE)
+    );
+}
+
+#[test]
+fn pause_until_tomorrow_morning() {
+    // Monday 15:00, without working hours morning is 08:00.
+    let mut harness = Harness::new(20 * MINUTE);
+    harness.clock.advance(15 * 60 * MINUTE);
+
+    harness.send(Message::PauseReminders(PauseLength::TomorrowMorning));
+    assert_eq!(
+        harness.notifier.next_deadline(),
+        Some(harness.clock.now() + 17 * 60 * MINUTE)
+    );
+
+    // Friday 15:00 with working hours pauses until Monday 08:30.
+    let mut harness = office_hours(5, 15, 0);
+    harness.send(Message::PauseReminders(PauseLength::TomorrowMorning));
+    assert_eq!(
+        harness.notifier.next_deadline(),
+        Some(harness.clock.now() + (2 * 24 + 17) * 60 * MINUTE + 30 * MINUTE)
+    );
+}
+
+#[test]
+fn pause_until_resumed_never_ends() {
+    let mut harness = Harness::new(20 * MINUTE);
+
+    harness.send(Message::PauseReminders(PauseLength::UntilResumed));
+    assert_eq!(harness.notifier.next_deadline(), None);
+    assert!(harness.run_for(7 * 24 * 60 * MINUTE).is_empty());
+    assert!(harness.notifier.is_paused());
+}
+
+#[test]
+fn event_loop_is_told_when_pause_starts_and_ends() {
+    // Monday 10:00.
+    let mut harness = Harness::new(20 * MINUTE);
+    harness.clock.advance(10 * 60 * MINUTE);
+
+    harness.send(Message::PauseReminders(PauseLength::For(30 * MINUTE)));
+    harness.run_for(30 * MINUTE);
+    harness.take_reminders();
+
+    let resumes = date(1).and_time(time(10, 30));
+    assert!(matches!(
+        harness.other_events.as_slice(),
+        [
+            NotifierEvent::Paused(Some(paused_until)),
+            NotifierEvent::Resumed
+        ] if *paused_until == resumes
+    ));
+    assert!(harness.notifier.paused.is_none());
+}
+
+#[test]
+fn step_wakes_on_message_before_timeout() {
+    let mut harness = Harness::new(20 * MINUTE);
+
+    harness.messages.send(Message::SetInterval(MINUTE)).unwrap();
+    harness.clock.advance(MINUTE);
+    assert!(harness
+        .notifier
+        .step(Some(Duration::from_secs(60 * 60)))
+        .is_ok());
+
+    assert_eq!(harness.take_reminders().len(), 1);
+}
+
+#[test]
+fn step_shuts_down_when_sender_disconnects() {
+    let Harness {
+        mut notifier,
+        messages,
+        ..
+    } = Harness::new(20 * MINUTE);
+
+    drop(messages);
+    assert!(notifier.step(None).is_err());
+}
+
+fn eyes_stretch_and_water() -> Harness {
+    Harness::with_schedules(vec![
+        schedule("Eyes", 20 * MINUTE, Duration::from_secs(20)),
+        schedule("Stretch", 60 * MINUTE, 5 * MINUTE),
+        schedule("Water", 90 * MINUTE, Duration::from_secs(30)),
+    ])
+}
+
+#[test]
+fn schedules_remind_independently() {
+    let mut harness = eyes_stretch_and_water();
+
+    let reminders = harness.run_for(20 * MINUTE);
+    assert_eq!(reminders.len(), 1);
+    assert_eq!(reminders[0].schedule, "Eyes");
+    harness.end_break(reminders[0].break_id);
+
+    let reminders = harness.run_for(20 * MINUTE);
+    assert_eq!(reminders.len(), 1);
+    assert_eq!(reminders[0].schedule, "Eyes");
+    harness.end_break(reminders[0].break_id);
+}
+
+#[test]
+fn longest_due_break_is_sent_and_resets_shorter_timers() {
+    let mut harness = eyes_stretch_and_water();
+
+    for _ in 0..2 {
+        let reminders = harness.run_for(20 * MINUTE);
+        assert_eq!(reminders[0].schedule, "Eyes");
+        harness.end_break(reminders[0].break_id);
+    }
+
+    // Eyes and stretch are both due at the hour, only the longer stretch break is sent.
+    let reminders = harness.run_for(20 * MINUTE);
+    assert_eq!(reminders.len(), 1);
+    assert_eq!(reminders[0].schedule, "Stretch");
+
+    // The eyes schedule waits for the stretch break rather than interrupting it.
+    assert!(harness.run_for(10 * MINUTE).is_empty());
+    harness.end_break(reminders[0].break_id);
+
+    // Eyes and water were both covered by the stretch break and restart from its end.
+    let reminders = harness.run_for(20 * MINUTE);
+    assert_eq!(reminders.len(), 1);
+    assert_eq!(reminders[0].schedule, "Eyes");
+
+    let water = &harness.notifier.schedules[2];
+    assert_eq!(
+        water.last_break.id,
+        harness.notifier.schedules[1].last_break.id
+    );
+}
+
+#[test]
+fn shorter_break_does_not_reset_longer_timer() {
+    let mut harness = eyes_stretch_and_water();
+
+    for _ in 0..2 {
+        let reminders = harness.run_for(20 * MINUTE);
+        harness.end_break(reminders[0].break_id);
+    }
+
+    let stretch = &harness.notifier.schedules[1];
+    assert_eq!(stretch.last_break.state(), BreakState::Scheduled);
+    let start = stretch.last_break.changed();
+    assert_eq!(stretch.next_due(&harness.clock), Some(start + 60 * MINUTE));
+    assert!(stretch.last_break.id.is_nil());
+}
+
+#[test]
+fn reminder_carries_break_settings() {
+    let mut harness = Harness::with_schedules(vec![ScheduleConfig {
+        auto_close: false,
+        enforce_break_length: true,
+        ..schedule("Stretch", 60 * MINUTE, 5 * MINUTE)
+    }]);
+
+    let reminders = harness.run_for(60 * MINUTE);
+    assert_eq!(reminders[0].length, 5 * MINUTE);
+    assert!(!reminders[0].auto_close);
+    assert!(reminders[0].enforce_length);
+}
+
+#[test]
+fn cut_short_break_only_covers_time_taken() {
+    let mut harness = eyes_stretch_and_water();
+
+    for _ in 0..2 {
+        let reminders = harness.run_for(20 * MINUTE);
+        harness.end_break(reminders[0].break_id);
+    }
+    let reminders = harness.run_for(20 * MINUTE);
+    assert_eq!(reminders[0].schedule, "Stretch");
+
+    // Dismissed after one minute of a five minute stretch break.
+    harness.run_for(MINUTE);
+    harness.end_break(reminders[0].break_id);
+
+    let stretch = &harness.notifier.schedules[1].last_break;
+    assert!(stretch.was_cut_short());
+    assert_eq!(stretch.taken(), Some(MINUTE));
+    assert!(stretch
+        .describe(harness.clock.now())
+        .contains("Skipped after 60 of 300 seconds"));
+
+    // The minute still covered the eyes and water breaks.
+    let eyes = &harness.notifier.schedules[0].last_break;
+    assert_eq!(eyes.id, stretch.id);
+    let water = &harness.notifier.schedules[2].last_break;
+    assert_eq!(water.id, stretch.id);
+}
+
+#[test]
+fn cut_short_break_does_not_cover_longer_breaks() {
+    let mut harness = Harness::with_schedules(vec![
+        schedule("Eyes", 20 * MINUTE, 2 * MINUTE),
+        schedule("Walk", 60 * MINUTE, MINUTE),
+    ]);
+
+    let reminders = harness.run_for(20 * MINUTE);
+    assert_eq!(reminders[0].schedule, "Eyes");
+    harness.end_break(reminders[0].break_id);
+
+    // Ended straight away, so the one minute walk break is not covered.
+    assert!(harness.notifier.schedules[0].last_break.was_cut_short());
+    assert!(harness.notifier.schedules[1].last_break.id.is_nil());
+}
+
+#[test]
+fn set_interval_applies_to_primary_schedule() {
+    let mut harness = eyes_stretch_and_water();
+
+    harness.send(Message::SetInterval(30 * MINUTE));
+    assert_eq!(harness.notifier.schedules[0].interval, 30 * MINUTE);
+    assert_eq!(harness.notifier.schedules[1].interval, 60 * MINUTE);
+}
+
+/// A 25 minute pomodoro with the default breaks.
+fn pomodoro_schedule() -> ScheduleConfig {
+    ScheduleConfig {
+        name: "Pomodoro".to_string(),
+        interval: 25 * MINUTE,
+        pomodoro: Some(PomodoroConfig::default()),
+        ..Default::default()
+    }
+}
+
+fn pomodoro() -> Harness {
+    Harness::with_schedules(vec![pomodoro_schedule()])
+}
+
+fn pomodoro_state(harness: &Harness) -> &crate::pomodoro::Pomodoro {
+    harness.notifier.schedules[0].pomodoro.as_ref().unwrap()
+}
+
+#[test]
+fn stopped_pomodoro_never_reminds() {
+    let mut harness = pomodoro();
+
+    assert!(harness.run_for(3 * 60 * MINUTE).is_empty());
+    assert_eq!(harness.notifier.next_deadline(), None);
+}
+
+#[test]
+fn pomodoro_alternates_short_and_long_breaks() {
+    let mut harness = pomodoro();
+    harness.send(Message::StartPomodoro(Some("Write report".to_string())));
+
+    let mut lengths = Vec::new();
+    for _ in 0..8 {
+        let reminders = harness.run_for(25 * MINUTE);
+        assert_eq!(reminders.len(), 1);
+
+        let length = harness.notifier.schedules[0].last_break.length;
+        lengths.push(length.as_secs() / 60);
+
+        harness.run_for(length);
+        harness.end_break(reminders[0].break_id);
+    }
+
+    assert_eq!(lengths, [5, 5, 5, 15, 5, 5, 5, 15]);
+
+    let pomodoro = pomodoro_state(&harness);
+    assert_eq!(pomodoro.cycle, 8);
+    assert_eq!(pomodoro.history.len(), 8);
+    assert_eq!(pomodoro.history[7].task.as_deref(), Some("Write report"));
+}
+
+#[test]
+fn skip_pomodoro_starts_break_now() {
+    let mut harness = pomodoro();
+    harness.send(Message::StartPomodoro(None));
+
+    assert!(harness.run_for(10 * MINUTE).is_empty());
+    harness.send(Message::SkipPomodoro);
+    assert_eq!(harness.take_reminders().len(), 1);
+    assert_eq!(pomodoro_state(&harness).cycle, 1);
+
+    // A second skip while the break is outstanding does nothing.
+    harness.send(Message::SkipPomodoro);
+    assert_eq!(harness.take_reminders().len(), 0);
+}
+
+#[test]
+fn reset_pomodoro_stops_and_clears_cycles() {
+    let mut harness = pomodoro();
+    harness.send(Message::StartPomodoro(Some("Emails".to_string())));
+
+    let reminders = harness.run_for(25 * MINUTE);
+    harness.end_break(reminders[0].break_id);
+    harness.send(Message::ResetPomodoro);
+
+    let pomodoro = pomodoro_state(&harness);
+    assert!(!pomodoro.running);
+    assert_eq!(pomodoro.cycle, 0);
+    assert!(pomodoro.task.is_none());
+    assert!(harness.run_for(60 * MINUTE).is_empty());
+}
+
+#[test]
+fn starting_pomodoro_restarts_work_period() {
+    let mut harness = pomodoro();
+    harness.send(Message::StartPomodoro(None));
+
+    assert!(harness.run_for(20 * MINUTE).is_empty());
+    harness.send(Message::StartPomodoro(Some("Review".to_string())));
+    assert!(harness.run_for(24 * MINUTE).is_empty());
+
+    let reminders = harness.run_for(MINUTE);
+    assert_eq!(reminders.len(), 1);
+    assert!(reminders[0].message.starts_with("Pomodoro 1 complete"));
+}
+
+fn snooze(harness: &mut Harness, id: Uuid) {
+    harness.send(Message::Snooze(id));
+}
+
+#[test]
+fn snoozed_break_is_sent_again_after_snooze() {
+    let mut harness = Harness::new(20 * MINUTE);
+
+    let reminders = harness.run_for(20 * MINUTE);
+    let id = reminders[0].break_id;
+    assert_eq!(reminders[0].snooze_length, Some(5 * MINUTE));
+    snooze(&mut harness, id);
+
+    assert_eq!(
+        harness.notifier.next_deadline(),
+        Some(harness.clock.now() + 5 * MINUTE)
+    );
+    assert!(harness.run_for(4 * MINUTE).is_empty());
+
+    // The same break is sent again rather than a new one.
+    let reminders = harness.run_for(MINUTE);
+    assert_eq!(reminders.len(), 1);
+    assert_eq!(reminders[0].break_id, id);
+    assert_eq!(harness.notifier.schedules[0].last_break.snoozes, 1);
+
+    // The interval restarts from when the snoozed break ends.
+    harness.run_for(5 * MINUTE);
+    harness.end_break(id);
+    assert!(!harness.notifier.schedules[0].last_break.was_cut_short());
+    assert!(harness.run_for(19 * MINUTE).is_empty());
+    assert_eq!(harness.run_for(MINUTE).len(), 1);
+}
+
+#[test]
+fn snooze_limit_is_enforced() {
+    let mut harness = Harness::with_schedules(vec![ScheduleConfig {
+        max_snoozes: 2,
+        ..schedule("Break", 20 * MINUTE, 5 * MINUTE)
+    }]);
+
+    let reminders = harness.run_for(20 * MINUTE);
+    let id = reminders[0].break_id;
+
+    let mut snooze_lengths = Vec::new();
+    for _ in 0..2 {
+        snooze(&mut harness, id);
+        let reminders = harness.run_for(5 * MINUTE);
+        assert_eq!(reminders.len(), 1);
+        assert_eq!(reminders[0].break_id, id);
+        snooze_lengths.push(reminders[0].snooze_length);
+    }
+
+    // The last reminder no longer offers a snooze, a snooze past the limit is sent again
+    // straight away.
+    assert_eq!(snooze_lengths, [Some(5 * MINUTE), None]);
+
+    snooze(&mut harness, id);
+    let reminders = harness.take_reminders();
+    assert_eq!(reminders.len(), 1);
+    assert_eq!(reminders[0].break_id, id);
+    assert_eq!(harness.notifier.schedules[0].last_break.snoozes, 2);
+}
+
+#[test]
+fn snoozed_break_blocks_shorter_schedules() {
+    let mut harness = eyes_stretch_and_water();
+
+    for _ in 0..2 {
+        let reminders = harness.run_for(20 * MINUTE);
+        harness.end_break(reminders[0].break_id);
+    }
+
+    let reminders = harness.run_for(20 * MINUTE);
+    assert_eq!(reminders[0].schedule, "Stretch");
+    snooze(&mut harness, reminders[0].break_id);
+
+    // Eyes does not interrupt the snoozed stretch break.
+    let reminders = harness.run_for(5 * MINUTE);
+    assert_eq!(reminders.len(), 1);
+    assert_eq!(reminders[0].schedule, "Stretch");
+}
+
+#[test]
+fn mismatched_snooze_is_ignored() {
+    let mut harness = Harness::new(20 * MINUTE);
+
+    harness.run_for(20 * MINUTE);
+    snooze(&mut harness, Uuid::new_v4());
+
+    assert!(!harness.notifier.schedules[0].is_snoozed());
+    assert!(harness.run_for(60 * MINUTE).is_empty());
+}
+
+/// Mon-Fri 08:30-17:30 with a lunch gap, starting at the given time and day of January 2024,
+/// where the 1st is a Monday.
+fn office_hours(day: u32, hour: u32, minute: u32) -> Harness {
+    let working_hours = toml::from_str::<Config>(
+        r#"
+        idle_threshold = "0s"
+
+        [[schedules]]
+        name = "Break"
+        interval = "20m"
+
+        [[working_hours]]
+        days = ["Mon", "Tue", "Wed", "Thu", "Fri"]
+        start = "08:30:00"
+        end = "12:00:00"
+
+        [[working_hours]]
+        days = ["Mon", "Tue", "Wed", "Thu", "Fri"]
+        start = "13:00:00"
+        end = "17:30:00"
+        "#,
+    )
+    .unwrap();
+
+    let start = date(day).and_time(time(hour, minute));
+    Harness::with_config(&working_hours, FakeClock::at(start))
+}
+
+#[test]
+fn first_reminder_is_an_interval_after_work_starts() {
+    let mut harness = office_hours(1, 8, 0);
+
+    // Before work the deadline is the start of the working window.
+    assert_eq!(
+        harness.notifier.next_deadline(),
+        Some(harness.clock.now() + 30 * MINUTE)
+    );
+
+    assert!(harness.run_for(30 * MINUTE).is_empty());
+    assert!(harness.run_for(19 * MINUTE).is_empty());
+    assert_eq!(harness.run_for(MINUTE).len(), 1);
+}
+
+#[test]
+fn no_reminders_over_lunch() {
+    let mut harness = office_hours(1, 11, 50);
+
+    // The interval started at 11:50 is due at 12:10, during lunch.
+    assert!(harness.run_for(69 * MINUTE).is_empty());
+    assert!(harness.notifier.schedules[0].is_due(&harness.clock));
+
+    // The timer restarts when work resumes at 13:00.
+    assert!(harness.run_for(20 * MINUTE).is_empty());
+    assert_eq!(harness.run_for(MINUTE).len(), 1);
+}
+
+#[test]
+fn no_reminders_over_the_weekend() {
+    // Friday 17:00.
+    let mut harness = office_hours(5, 17, 0);
+
+    let reminders = harness.run_for(20 * MINUTE);
+    assert_eq!(reminders.len(), 1);
+    harness.end_break(reminders[0].break_id);
+
+    // The next reminder would be due at 17:40, after work ends.
+    assert_eq!(
+        harness.notifier.next_deadline(),
+        Some(harness.clock.now() + 20 * MINUTE)
+    );
+
+    // Friday 17:20 to Monday 08:30.
+    let until_monday = (15 * 60 + 10) * MINUTE + 2 * 24 * 60 * MINUTE;
+    assert!(harness.run_for(until_monday).is_empty());
+    assert_eq!(harness.clock.local_now().weekday(), Weekday::Mon);
+
+    assert!(harness.run_for(19 * MINUTE).is_empty());
+    assert_eq!(harness.run_for(MINUTE).len(), 1);
+}
+
+#[test]
+fn deadline_outside_working_hours_is_next_window() {
+    // Saturday 10:00.
+    let harness = office_hours(6, 10, 0);
+
+    assert!(!harness.notifier.is_working());
+    assert_eq!(
+        harness.notifier.next_deadline(),
+        Some(harness.clock.now() + (46 * 60 + 30) * MINUTE)
+    );
+}
+
+fn idle_detection(schedules: Vec<ScheduleConfig>) -> Harness {
+    let config = Config {
+        schedules,
+        idle_threshold: 5 * MINUTE,
+        ..Default::default()
+    };
+    Harness::with_config(&config, FakeClock::default())
+}
+
+#[test]
+fn time_away_counts_as_a_break() {
+    let mut harness = idle_detection(vec![schedule("Break", 20 * MINUTE, 5 * MINUTE)]);
+
+    assert!(harness.work_for(10 * MINUTE).is_empty());
+
+    // Away from 10 to 25 minutes, the reminder due at 20 minutes is held back.
+    assert!(harness.run_for(15 * MINUTE).is_empty());
+    assert!(harness.notifier.away_since.is_some());
+
+    // Back at 26 minutes, the interval restarts from then.
+    assert!(harness.work_for(20 * MINUTE).is_empty());
+    assert!(harness.notifier.away_since.is_none());
+    assert_eq!(harness.work_for(MINUTE).len(), 1);
+}
+
+#[test]
+fn short_idle_is_not_a_break() {
+    let mut harness = idle_detection(vec![schedule("Break", 20 * MINUTE, 5 * MINUTE)]);
+
+    assert!(harness.work_for(10 * MINUTE).is_empty());
+    assert!(harness.run_for(4 * MINUTE).is_empty());
+    assert!(harness.notifier.away_since.is_none());
+
+    assert!(harness.work_for(5 * MINUTE).is_empty());
+    assert_eq!(harness.work_for(MINUTE).len(), 1);
+}
+
+#[test]
+fn time_away_only_covers_shorter_breaks() {
+    let mut harness = idle_detection(vec![
+        schedule("Eyes", 20 * MINUTE, Duration::from_secs(20)),
+        schedule("Stretch", 40 * MINUTE, 10 * MINUTE),
+    ]);
+
+    assert!(harness.work_for(15 * MINUTE).is_empty());
+    assert!(harness.run_for(7 * MINUTE).is_empty());
+
+    // Back at 23 minutes, the eyes timer restarted but the stretch timer did not.
+    assert!(harness.work_for(17 * MINUTE).is_empty());
+    let reminders = harness.work_for(MINUTE);
+    assert_eq!(reminders.len(), 1);
+    assert_eq!(reminders[0].schedule, "Stretch");
+}
+
+#[test]
+fn idle_detection_polls_when_the_threshold_could_be_reached() {
+    let mut harness = idle_detection(vec![schedule("Break", 20 * MINUTE, 5 * MINUTE)]);
+
+    assert_eq!(
+        harness.notifier.next_deadline(),
+        Some(harness.clock.now() + 5 * MINUTE)
+    );
+
+    // The deadline is still polled while the break is outstanding, from the last input.
+    harness.work_for(20 * MINUTE);
+    assert!(harness.notifier.schedules[0].is_outstanding());
+    harness.clock.advance(2 * MINUTE);
+    assert_eq!(
+        harness.notifier.next_deadline(),
+        Some(harness.clock.now() + 3 * MINUTE)
+    );
+
+    // While away the idle time is checked regularly to notice the return.
+    harness.run_for(5 * MINUTE);
+    assert!(harness.notifier.away_since.is_some());
+    assert_eq!(
+        harness.notifier.next_deadline(),
+        Some(harness.clock.now() + Duration::from_secs(30))
+    );
+}
+
+#[test]
+fn break_held_back_while_away_is_not_a_deadline() {
+    let mut harness = idle_detection(vec![schedule("Break", 20 * MINUTE, 5 * MINUTE)]);
+
+    // Away from 10 minutes, the break due at 20 minutes is held back.
+    harness.work_for(10 * MINUTE);
+    assert!(harness.run_for(15 * MINUTE).is_empty());
+    assert!(harness.notifier.away_since.is_some());
+    assert_eq!(
+        harness.notifier.next_deadline(),
+        Some(harness.clock.now() + Duration::from_secs(30))
+    );
+}
+
+fn resume_behaviour(resume: ResumeBehaviour) -> Harness {
+    let config = Config {
+        resume,
+        ..base_config(vec![schedule("Break", 20 * MINUTE, 5 * MINUTE)])
+    };
+    Harness::with_config(&config, FakeClock::default())
+}
+
+#[test]
+fn resume_ends_pause_early() {
+    let mut harness = resume_behaviour(ResumeBehaviour::RemindIfOverdue);
+
+    harness.send(Message::PauseReminders(PauseLength::UntilResumed));
+    assert!(harness.run_for(10 * MINUTE).is_empty());
+
+    harness.send(Message::Resume);
+    assert!(!harness.notifier.is_paused());
+    assert!(harness.run_for(9 * MINUTE).is_empty());
+    assert_eq!(harness.run_for(MINUTE).len(), 1);
+
+    assert!(matches!(
+        harness.other_events.as_slice(),
+        [NotifierEvent::Paused(None), NotifierEvent::Resumed]
+    ));
+}
+
+#[test]
+fn resume_sends_overdue_break() {
+    let mut harness = resume_behaviour(ResumeBehaviour::RemindIfOverdue);
+
+    harness.send(Message::PauseReminders(PauseLength::For(60 * MINUTE)));
+    assert!(harness.run_for(30 * MINUTE).is_empty());
+
+    harness.send(Message::Resume);
+    assert_eq!(harness.take_reminders().len(), 1);
+}
+
+#[test]
+fn resume_restarts_interval_when_configured() {
+    let mut harness = resume_behaviour(ResumeBehaviour::RestartInterval);
+
+    harness.send(Message::PauseReminders(PauseLength::For(60 * MINUTE)));
+    assert!(harness.run_for(30 * MINUTE).is_empty());
+
+    harness.send(Message::Resume);
+    assert!(harness.take_reminders().is_empty());
+    assert!(harness.run_for(19 * MINUTE).is_empty());
+    let reminders = harness.run_for(MINUTE);
+    assert_eq!(reminders.len(), 1);
+    harness.end_break(reminders[0].break_id);
+
+    // The same applies when a pause runs out.
+    harness.send(Message::PauseReminders(PauseLength::For(30 * MINUTE)));
+    assert!(harness.run_for(30 * MINUTE).is_empty());
+    assert!(harness.run_for(19 * MINUTE).is_empty());
+    assert_eq!(harness.run_for(MINUTE).len(), 1);
+}
+
+#[test]
+fn resume_without_pause_is_ignored() {
+    let mut harness = resume_behaviour(ResumeBehaviour::RestartInterval);
+
+    assert!(harness.run_for(10 * MINUTE).is_empty());
+    harness.send(Message::Resume);
+
+    assert!(harness.other_events.is_empty());
+    assert!(harness.run_for(9 * MINUTE).is_empty());
+    assert_eq!(harness.run_for(MINUTE).len(), 1);
+}
+
+#[test]
+fn sleep_counts_as_a_break() {
+    let mut harness = Harness::new(20 * MINUTE);
+
+    assert!(harness.run_for(10 * MINUTE).is_empty());
+
+    // Asleep overnight where the monotonic clock stops while suspended.
+    harness.clock.advance_wall(chrono::TimeDelta::hours(8));
+    harness.step();
+    assert!(harness.take_reminders().is_empty());
+
+    let last_break = &harness.notifier.schedules[0].last_break;
+    assert_eq!(last_break.taken(), Some(8 * 60 * MINUTE));
+
+    // The interval restarts from waking up.
+    assert!(harness.run_for(19 * MINUTE).is_empty());
+    assert_eq!(harness.run_for(MINUTE).len(), 1);
+}
+
+#[test]
+fn overrun_wait_counts_as_a_break() {
+    let mut harness = Harness::new(20 * MINUTE);
+
+    // Asleep overnight where the monotonic clock keeps counting, so the wait for the 20 minute
+    // deadline overruns.
+    let waited_from = harness.clock.now();
+    harness.clock.advance(8 * 60 * MINUTE);
+    harness
+        .notifier
+        .detect_sleep(waited_from, Some(20 * MINUTE));
+
+    let last_break = &harness.notifier.schedules[0].last_break;
+    assert_eq!(last_break.taken(), Some(8 * 60 * MINUTE - 20 * MINUTE));
+    assert!(!harness.notifier.schedules[0].is_due(&harness.clock));
+}
+
+#[test]
+fn short_clock_gap_is_not_a_break() {
+    let mut harness = Harness::new(20 * MINUTE);
+
+    assert!(harness.run_for(10 * MINUTE).is_empty());
+    harness.clock.advance_wall(chrono::TimeDelta::seconds(30));
+    assert!(harness.run_for(9 * MINUTE).is_empty());
+    assert_eq!(harness.run_for(MINUTE).len(), 1);
+}
+
+#[test]
+fn clock_moving_back_is_not_a_break() {
+    let mut harness = Harness::new(20 * MINUTE);
+
+    assert!(harness.run_for(10 * MINUTE).is_empty());
+    harness.clock.advance_wall(chrono::TimeDelta::hours(-2));
+    assert!(harness.run_for(9 * MINUTE).is_empty());
+    assert_eq!(harness.run_for(MINUTE).len(), 1);
+}
+
+#[test]
+fn pause_ends_on_time_across_sleep() {
+    // Monday 10:00.
+    let mut harness = Harness::new(20 * MINUTE);
+    harness.clock.advance(10 * 60 * MINUTE);
+    harness.send(Message::PauseReminders(PauseLength::Until(time(14, 0))));
+
+    // Asleep for three hours with the monotonic clock stopped, the pause still ends at 14:00.
+    harness.clock.advance_wall(chrono::TimeDelta::hours(3));
+    harness.step();
+    assert_eq!(
+        harness.notifier.next_deadline(),
+        Some(harness.clock.now() + 60 * MINUTE)
+    );
+}
+
+fn escalating() -> Harness {
+    Harness::with_schedules(vec![ScheduleConfig {
+        escalation: Some(EscalationConfig {
+            delay: 2 * MINUTE,
+            steps: vec![EscalationStep::Renotify, EscalationStep::FullScreen],
+        }),
+        ..schedule("Break", 20 * MINUTE, 5 * MINUTE)
+    }])
+}
+
+#[test]
+fn ignored_reminder_escalates_after_each_delay() {
+    let mut harness = escalating();
+
+    let reminders = harness.run_for(20 * MINUTE);
+    let level = reminders[0].escalation_level.clone();
+    assert_eq!(
+        reminders[0].escalation_steps,
+        [EscalationStep::Renotify, EscalationStep::FullScreen]
+    );
+    assert_eq!(
+        harness.notifier.next_deadline(),
+        Some(harness.clock.now() + 2 * MINUTE)
+    );
+
+    harness.run_for(MINUTE);
+    assert_eq!(level.get(), 0);
+    harness.run_for(MINUTE);
+    assert_eq!(level.get(), 1);
+    harness.run_for(2 * MINUTE);
+    assert_eq!(level.get(), 2);
+
+    // Once every step is reached nothing more is due until the break ends.
+    harness.run_for(10 * MINUTE);
+    let last_break = &harness.notifier.schedules[0].last_break;
+    let steps: Vec<_> = last_break.escalations.iter().map(|e| e.step).collect();
+    assert_eq!(
+        steps,
+        [EscalationStep::Renotify, EscalationStep::FullScreen]
+    );
+    assert_eq!(harness.notifier.next_deadline(), None);
+}
+
+#[test]
+fn ended_break_does_not_escalate() {
+    let mut harness = escalating();
+
+    let reminders = harness.run_for(20 * MINUTE);
+    harness.end_break(reminders[0].break_id);
+    harness.run_for(5 * MINUTE);
+
+    assert!(harness.notifier.schedules[0]
+        .last_break
+        .escalations
+        .is_empty());
+    assert_eq!(reminders[0].escalation_level.get(), 0);
+}
+
+#[test]
+fn shown_reminder_escalates_until_answered() {
+    let mut harness = escalating();
+
+    // The event loop acknowledges and starts the break as soon as the reminder is shown.
+    let reminders = harness.run_for(20 * MINUTE);
+    let id = reminders[0].break_id;
+    harness.send(Message::AcknowledgeBreak(id));
+    harness.send(Message::StartBreak(id));
+    assert_eq!(
+        harness.notifier.next_escalation(),
+        Some(harness.clock.now() + 2 * MINUTE)
+    );
+
+    harness.run_for(2 * MINUTE);
+    assert_eq!(reminders[0].escalation_level.get(), 1);
+
+    harness.end_break(id);
+    harness.run_for(5 * MINUTE);
+    assert_eq!(reminders[0].escalation_level.get(), 1);
+}
+
+#[test]
+fn snoozed_break_does_not_escalate_until_sent_again() {
+    let mut harness = escalating();
+
+    let reminders = harness.run_for(20 * MINUTE);
+    snooze(&mut harness, reminders[0].break_id);
+    harness.run_for(4 * MINUTE);
+    assert!(harness.notifier.schedules[0]
+        .last_break
+        .escalations
+        .is_empty());
+
+    // The delay restarts from when the reminder is sent again.
+    assert_eq!(harness.run_for(MINUTE).len(), 1);
+    harness.run_for(MINUTE);
+    assert!(harness.notifier.schedules[0]
+        .last_break
+        .escalations
+        .is_empty());
+    harness.run_for(MINUTE);
+    assert_eq!(
+        harness.notifier.schedules[0].last_break.escalations.len(),
+        1
+    );
+}
+
+fn adaptive() -> Harness {
+    Harness::with_schedules(vec![ScheduleConfig {
+        adaptive: Some(AdaptiveConfig {
+            min_interval: 10 * MINUTE,
+            max_interval: 30 * MINUTE,
+            step: 5 * MINUTE,
+            history: 2,
+        }),
+        ..schedule("Break", 20 * MINUTE, 5 * MINUTE)
+    }])
+}
+
+/// Waits for the next reminder, then takes a break of the given length, snoozing it first if
+/// asked.
+fn take_break(harness: &mut Harness, snooze_first: bool, taken: Duration) {
+    let interval = harness.notifier.schedules[0].interval;
+    let reminders = harness.run_for(interval);
+    assert_eq!(reminders.len(), 1);
+    let id = reminders[0].break_id;
+
+    if snooze_first {
+        snooze(harness, id);
+        assert_eq!(harness.run_for(5 * MINUTE).len(), 1);
+    }
+
+    harness.run_for(taken);
+    harness.end_break(id);
+}
+
+#[test]
+fn mixed_breaks_leave_an_interval_outside_the_bounds() {
+    let mut harness = adaptive();
+    harness.send(Message::SetInterval(60 * MINUTE));
+
+    take_break(&mut harness, false, 5 * MINUTE);
+    take_break(&mut harness, false, Duration::ZERO);
+
+    let schedule = &harness.notifier.schedules[0];
+    assert_eq!(schedule.interval, 60 * MINUTE);
+    assert!(schedule
+        .adaptive
+        .as_ref()
+        .unwrap()
+        .last_adjustment
+        .is_none());
+}
+
+#[test]
+fn snoozed_breaks_lengthen_interval() {
+    let mut harness = adaptive();
+
+    take_break(&mut harness, true, 5 * MINUTE);
+    assert_eq!(harness.notifier.schedules[0].interval, 20 * MINUTE);
+    take_break(&mut harness, true, 5 * MINUTE);
+    assert_eq!(harness.notifier.schedules[0].interval, 25 * MINUTE);
+
+    let description = harness.notifier.schedules[0].describe(&harness.clock);
+    assert!(description.contains("2 of the last 2 breaks were snoozed"));
+
+    // The longer interval applies to the next break.
+    assert!(harness.run_for(24 * MINUTE).is_empty());
+    assert_eq!(harness.run_for(MINUTE).len(), 1);
+}
+
+#[test]
+fn skipped_breaks_shorten_interval_within_bounds() {
+    let mut harness = adaptive();
+
+    for expected in [15, 10, 10] {
+        take_break(&mut harness, false, Duration::ZERO);
+        take_break(&mut harness, false, MINUTE * 2);
+        assert_eq!(harness.notifier.schedules[0].interval, expected * MINUTE);
+    }
+}
+
+#[test]
+fn full_breaks_return_interval_to_base() {
+    let mut harness = adaptive();
+
+    for _ in 0..2 {
+        take_break(&mut harness, false, Duration::ZERO);
+    }
+    assert_eq!(harness.notifier.schedules[0].interval, 15 * MINUTE);
+
+    for _ in 0..2 {
+        take_break(&mut harness, false, 5 * MINUTE);
+    }
+    assert_eq!(harness.notifier.schedules[0].interval, 20 * MINUTE);
+
+    // Full breaks at the base interval leave it alone.
+    for _ in 0..4 {
+        take_break(&mut harness, false, 5 * MINUTE);
+    }
+    assert_eq!(harness.notifier.schedules[0].interval, 20 * MINUTE);
+}
+
+#[test]
+fn mixed_behaviour_does_not_adjust_interval() {
+    let mut harness = adaptive();
+
+    take_break(&mut harness, true, 5 * MINUTE);
+    take_break(&mut harness, false, Duration::ZERO);
+    take_break(&mut harness, false, 5 * MINUTE);
+
+    assert_eq!(harness.notifier.schedules[0].interval, 20 * MINUTE);
+}
+
+#[test]
+fn set_interval_replaces_adaptive_base() {
+    let mut harness = adaptive();
+
+    take_break(&mut harness, false, Duration::ZERO);
+    harness.send(Message::SetInterval(30 * MINUTE));
+    take_break(&mut harness, false, Duration::ZERO);
+
+    // The skipped break before the change no longer counts.
+    let schedule = &harness.notifier.schedules[0];
+    assert_eq!(schedule.interval, 30 * MINUTE);
+    assert_eq!(schedule.adaptive.as_ref().unwrap().base, 30 * MINUTE);
+}
+
+fn catalogue(schedules: Vec<ScheduleConfig>) -> Harness {
+    let catalogue = toml::from_str::<Catalogue>(
+        r#"
+        avoid_repeats = 2
+
+        [[messages]]
+        text = "Look out of a window."
+        tags = ["eyes"]
+
+        [[messages]]
+        text = "Stretch your arms."
+        weight = 3
+        tags = ["stretch"]
+
+        [[messages]]
+        text = "Walk around."
+        tags = ["stretch"]
+
+        [[messages]]
+        text = "Never shown."
+        weight = 0
+        "#,
+    )
+    .unwrap();
+    let config = Config {
+        schedules,
+        idle_threshold: Duration::ZERO,
+        catalogue,
+        ..Default::default()
+    };
+
+    Harness::with_config(&config, FakeClock::default())
+}
+
+/// Runs the first schedule's interval the given number of times, ending each break straight
+/// away, and returns the reminder messages.
+fn reminder_messages(harness: &mut Harness, count: usize) -> Vec<String> {
+    let mut messages = Vec::new();
+    for _ in 0..count {
+        let interval = harness.notifier.schedules[0].interval;
+        let reminders = harness.run_for(interval);
+        harness.end_break(reminders[0].break_id);
+        messages.push(reminders[0].message.clone());
+    }
+    messages
+}
+
+#[test]
+fn catalogue_messages_do_not_repeat_recent_ones() {
+    let mut harness = catalogue(vec![schedule("Break", 20 * MINUTE, 5 * MINUTE)]);
+
+    let messages = reminder_messages(&mut harness, 12);
+    for window in messages.windows(3) {
+        assert_ne!(window[0], window[1]);
+        assert_ne!(window[0], window[2]);
+        assert_ne!(window[1], window[2]);
+    }
+    assert!(!messages.iter().any(|message| message == "Never shown."));
+}
+
+#[test]
+fn catalogue_messages_are_filtered_by_schedule_tags() {
+    let mut harness = catalogue(vec![ScheduleConfig {
+        message_tags: vec!["stretch".to_string()],
+        ..schedule("Stretch", 20 * MINUTE, 5 * MINUTE)
+    }]);
+
+    // With fewer matching messages than recent ones, the two stretch messages alternate.
+    let messages = reminder_messages(&mut harness, 4);
+    assert!(messages
+        .iter()
+        .all(|message| message == "Stretch your arms." || message == "Walk around."));
+    assert_ne!(messages[0], messages[1]);
+}
+
+#[test]
+fn schedule_message_is_used_without_matching_catalogue_messages() {
+    let mut harness = catalogue(vec![ScheduleConfig {
+        message_tags: vec!["water".to_string()],
+        message: "Drink some water.".to_string(),
+        ..schedule("Water", 20 * MINUTE, 5 * MINUTE)
+    }]);
+
+    assert_eq!(reminder_messages(&mut harness, 2), ["Drink some water."; 2]);
+}
+
+#[test]
+fn snoozed_break_keeps_its_activity() {
+    let mut harness = catalogue(vec![schedule("Break", 20 * MINUTE, 5 * MINUTE)]);
+
+    let reminders = harness.run_for(20 * MINUTE);
+    snooze(&mut harness, reminders[0].break_id);
+    let resent = harness.run_for(5 * MINUTE);
+
+    assert_eq!(resent[0].message, reminders[0].message);
+}
+
+fn routines() -> Harness {
+    let routines = toml::from_str::<Routines>(
+        r#"
+        [[routines]]
+        name = "Desk stretches"
+        steps = [
+            { text = "Neck rolls", length = "30s" },
+            { text = "Wrist stretch", length = "30s" },
+            { text = "Look at something 6m away", length = "20s" },
+        ]
+        "#,
+    )
+    .unwrap();
+    let config = Config {
+        routines,
+        ..base_config(vec![
+            ScheduleConfig {
+                routine: Some("Desk stretches".to_string()),
+                ..schedule("Stretch", 20 * MINUTE, 5 * MINUTE)
+            },
+            schedule("Eyes", 15 * MINUTE, Duration::ZERO),
+        ])
+    };
+
+    Harness::with_config(&config, FakeClock::default())
+}
+
+#[test]
+fn reminder_carries_schedule_routine() {
+    let mut harness = routines();
+
+    let reminders = harness.run_for(15 * MINUTE);
+    assert!(reminders[0].routine.is_none());
+    assert!(harness.notifier.schedules[1].last_break.routine.is_none());
+    harness.end_break(reminders[0].break_id);
+
+    let reminders = harness.run_for(5 * MINUTE);
+    let routine = reminders[0].routine.as_ref().unwrap();
+    let steps: Vec<_> = routine
+        .steps
+        .iter()
+        .map(|step| step.text.as_str())
+        .collect();
+    assert_eq!(
+        steps,
+        ["Neck rolls", "Wrist stretch", "Look at something 6m away"]
+    );
+
+    let recorded = harness.notifier.schedules[0].last_break.routine.as_ref();
+    assert_eq!(recorded.unwrap().name, "Desk stretches");
+}
+
+#[test]
+fn break_records_routine_completion() {
+    let mut harness = routines();
+
+    let reminders = harness.run_for(20 * MINUTE);
+    let reminder = reminders
+        .iter()
+        .find(|reminder| reminder.schedule == "Stretch")
+        .unwrap();
+
+    // The reminder reports its progress as the steps are walked through.
+    reminder.routine_progress.set(2);
+    let recorded = harness.notifier.schedules[0].last_break.routine.clone();
+    assert!(!recorded.as_ref().unwrap().is_completed());
+
+    reminder.routine_progress.set(3);
+    harness.end_break(reminder.break_id);
+
+    let last_break = &harness.notifier.schedules[0].last_break;
+    assert!(last_break.routine.as_ref().unwrap().is_completed());
+    assert!(last_break
+        .describe(harness.clock.now())
+        .contains("routine: \"Desk stretches\" 3 of 3 steps"));
+}
+
+fn states(harness: &Harness) -> Vec<BreakState> {
+    harness.notifier.schedules[0]
+        .last_break
+        .transitions()
+        .iter()
+        .map(|transition| transition.state)
+        .collect()
+}
+
+#[test]
+fn break_moves_through_its_lifecycle() {
+    let mut harness = Harness::new(20 * MINUTE);
+
+    let reminders = harness.run_for(20 * MINUTE);
+    let id = reminders[0].break_id;
+    let notified = harness.clock.now();
+    harness.send(Message::AcknowledgeBreak(id));
+    harness.send(Message::StartBreak(id));
+    harness.run_for(5 * MINUTE);
+    harness.end_break(id);
+
+    assert_eq!(
+        states(&harness),
+        [
+            BreakState::Scheduled,
+            BreakState::Notified,
+            BreakState::Acknowledged,
+            BreakState::InProgress,
+            BreakState::Completed,
+        ]
+    );
+    let last_break = &harness.notifier.schedules[0].last_break;
+    assert_eq!(last_break.notified(), Some(notified));
+    assert_eq!(last_break.ended(), Some(notified + 5 * MINUTE));
+    assert_eq!(last_break.taken(), Some(5 * MINUTE));
+}
+
+#[test]
+fn break_taken_from_countdown_start() {
+    let mut harness = Harness::new(20 * MINUTE);
+
+    let reminders = harness.run_for(20 * MINUTE);
+    let id = reminders[0].break_id;
+    harness.send(Message::AcknowledgeBreak(id));
+    harness.run_for(MINUTE);
+    harness.send(Message::StartBreak(id));
+    harness.run_for(4 * MINUTE);
+    harness.end_break(id);
+
+    let last_break = &harness.notifier.schedules[0].last_break;
+    assert_eq!(last_break.state(), BreakState::Skipped);
+    assert_eq!(last_break.taken(), Some(4 * MINUTE));
+}
+
+#[test]
+fn invalid_transitions_are_rejected() {
+    let mut harness = Harness::new(20 * MINUTE);
+
+    let reminders = harness.run_for(20 * MINUTE);
+    let id = reminders[0].break_id;
+    snooze(&mut harness, id);
+    snooze(&mut harness, id);
+    harness.send(Message::StartBreak(id));
+    assert_eq!(harness.notifier.schedules[0].last_break.snoozes, 1);
+
+    assert_eq!(harness.run_for(5 * MINUTE).len(), 1);
+    let resent = harness.clock.now();
+    harness.run_for(5 * MINUTE);
+    harness.end_break(id);
+    harness.end_break(id);
+    harness.send(Message::AcknowledgeBreak(id));
+
+    assert_eq!(
+        states(&harness),
+        [
+            BreakState::Scheduled,
+            BreakState::Notified,
+            BreakState::Snoozed(resent),
+            BreakState::Notified,
+            BreakState::Completed,
+        ]
+    );
+}
+
+#[test]
+fn restarted_interval_expires_snoozed_break() {
+    let mut harness = resume_behaviour(ResumeBehaviour::RestartInterval);
+
+    let reminders = harness.run_for(20 * MINUTE);
+    snooze(&mut harness, reminders[0].break_id);
+    harness.send(Message::PauseReminders(PauseLength::UntilResumed));
+    harness.run_for(30 * MINUTE);
+    harness.send(Message::Resume);
+
+    let last_break = &harness.notifier.schedules[0].last_break;
+    assert_eq!(last_break.state(), BreakState::Expired);
+    assert_eq!(last_break.ended(), Some(harness.clock.now()));
+    assert!(harness.run_for(19 * MINUTE).is_empty());
+    assert_eq!(harness.run_for(MINUTE).len(), 1);
+}
+
+fn work_cap(work_cap: WorkCapConfig) -> Harness {
+    let config = Config {
+        work_cap: Some(work_cap),
+        ..base_config(vec![
+            schedule("Eyes", 20 * MINUTE, Duration::ZERO),
+            schedule("Stretch", 45 * MINUTE, 5 * MINUTE),
+        ])
+    };
+    Harness::with_config(&config, FakeClock::default())
+}
+
+#[test]
+fn work_cap_forces_reminder_while_paused() {
+    let mut harness = work_cap(WorkCapConfig::default());
+
+    harness.send(Message::PauseReminders(PauseLength::UntilResumed));
+    assert!(harness.run_for(89 * MINUTE).is_empty());
+
+    let reminders = harness.run_for(MINUTE);
+    assert_eq!(reminders.len(), 1);
+    assert_eq!(reminders[0].schedule, "Stretch");
+    assert!(reminders[0]
+        .message
+        .starts_with("You have worked for 90 minutes without a real break."));
+    assert!(harness.notifier.is_paused());
+
+    // A real break starts a new stretch of work.
+    harness.run_for(5 * MINUTE);
+    harness.end_break(reminders[0].break_id);
+    assert!(harness.run_for(89 * MINUTE).is_empty());
+    assert_eq!(harness.run_for(MINUTE).len(), 1);
+}
+
+#[test]
+fn work_cap_does_not_force_stopped_pomodoros_or_fixed_times() {
+    let config = Config {
+        work_cap: Some(WorkCapConfig::default()),
+        ..base_config(vec![
+            schedule("Stretch", 45 * MINUTE, 5 * MINUTE),
+            pomodoro_schedule(),
+            ScheduleConfig {
+                times: vec![time(12, 30)],
+                ..schedule("Lunch", 20 * MINUTE, 60 * MINUTE)
+            },
+        ])
+    };
+    let mut harness = Harness::with_config(&config, FakeClock::default());
+
+    harness.send(Message::PauseReminders(PauseLength::UntilResumed));
+    let reminders = harness.run_for(90 * MINUTE);
+    assert_eq!(reminders.len(), 1);
+    assert_eq!(reminders[0].schedule, "Stretch");
+
+    let pomodoro = harness.notifier.schedules[1].pomodoro.as_ref().unwrap();
+    assert!(pomodoro.history.is_empty());
+}
+
+#[test]
+fn work_cap_without_a_schedule_to_force_has_no_deadline() {
+    let config = Config {
+        work_cap: Some(WorkCapConfig::default()),
+        ..base_config(vec![pomodoro_schedule()])
+    };
+    let mut harness = Harness::with_config(&config, FakeClock::default());
+
+    assert!(harness.run_for(200 * MINUTE).is_empty());
+    assert!(harness
+        .notifier
+        .next_deadline()
+        .is_none_or(|deadline| deadline > harness.clock.now()));
+}
+
+#[test]
+fn forced_break_does_not_complete_a_pomodoro() {
+    let config = Config {
+        work_cap: Some(WorkCapConfig {
+            max_work: 10 * MINUTE,
+            ..Default::default()
+        }),
+        ..base_config(vec![pomodoro_schedule()])
+    };
+    let mut harness = Harness::with_config(&config, FakeClock::default());
+    harness.send(Message::StartPomodoro(None));
+
+    let reminders = harness.run_for(10 * MINUTE);
+    assert_eq!(reminders.len(), 1);
+    assert!(!reminders[0].message.contains("Pomodoro 1 complete"));
+
+    let pomodoro = pomodoro_state(&harness);
+    assert_eq!(pomodoro.cycle, 0);
+    assert!(pomodoro.history.is_empty());
+}
+
+#[test]
+fn skipped_forced_reminder_is_repeated() {
+    let mut harness = work_cap(WorkCapConfig::default());
+
+    harness.send(Message::PauseReminders(PauseLength::UntilResumed));
+    let reminders = harness.run_for(90 * MINUTE);
+    harness.run_for(MINUTE);
+    harness.end_break(reminders[0].break_id);
+
+    // A minute away is not a real break, so another reminder is forced once the repeat delay
+    // since the first has passed.
+    assert!(harness.run_for(8 * MINUTE).is_empty());
+    assert_eq!(harness.run_for(MINUTE).len(), 1);
+}
+
+#[test]
+fn work_cap_sends_snoozed_break_early() {
+    let mut harness = Harness::with_schedules(vec![ScheduleConfig {
+        snooze_length: 30 * MINUTE,
+        max_snoozes: 10,
+        ..schedule("Break", 20 * MINUTE, 5 * MINUTE)
+    }]);
+    harness.notifier.work_cap = Some(WorkCapConfig::default());
+
+    let reminders = harness.run_for(20 * MINUTE);
+    let id = reminders[0].break_id;
+    snooze(&mut harness, id);
+    assert_eq!(harness.run_for(30 * MINUTE).len(), 1);
+    snooze(&mut harness, id);
+
+    // The second snooze would end at 80 minutes, the cap is exceeded at 90.
+    assert_eq!(harness.run_for(30 * MINUTE).len(), 1);
+    snooze(&mut harness, id);
+    let reminders = harness.run_for(10 * MINUTE);
+    assert_eq!(reminders.len(), 1);
+    assert_eq!(reminders[0].break_id, id);
+    assert!(harness.notifier.schedules[0].last_break.state().is_shown());
+}
+
+#[test]
+fn work_cap_can_respect_pauses_and_snoozes() {
+    let mut harness = work_cap(WorkCapConfig {
+        override_pause: false,
+        override_snooze: false,
+        ..Default::default()
+    });
+
+    harness.send(Message::PauseReminders(PauseLength::UntilResumed));
+    assert!(harness.run_for(4 * 60 * MINUTE).is_empty());
+    assert_eq!(harness.notifier.next_deadline(), None);
+
+    // Once resumed the cap applies straight away.
+    harness.send(Message::Resume);
+    let reminders = harness.take_reminders();
+    assert_eq!(reminders.len(), 1);
+    assert!(reminders[0]
+        .message
+        .starts_with("You have worked for 240 minutes"));
+}
+
+#[test]
+fn regular_breaks_stay_under_work_cap() {
+    let mut harness = work_cap(WorkCapConfig::default());
+
+    for _ in 0..6 * 60 {
+        for reminder in harness.run_for(MINUTE) {
+            assert!(!reminder.message.starts_with("You have worked"));
+            harness.run_for(reminder.length);
+            harness.end_break(reminder.break_id);
+        }
+    }
+}
+
+fn daily_limit(clock: FakeClock, screen_time: MemoryStore) -> Harness {
+    let config = Config {
+        schedules: vec![schedule("Break", 24 * 60 * MINUTE, 5 * MINUTE)],
+        idle_threshold: 5 * MINUTE,
+        daily_limit: Some(DailyLimitConfig::default()),
+        ..Default::default()
+    };
+    Harness::with_store(&config, clock, screen_time)
+}
+
+/// The active time of each daily limit notification sent so far.
+fn daily_limits(harness: &mut Harness) -> Vec<Duration> {
+    harness.take_reminders();
+    harness
+        .other_events
+        .iter()
+        .filter_map(|event| match event {
+            NotifierEvent::DailyLimit { active, .. } => Some(*active),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn screen_time_accumulates_while_active() {
+    let mut harness = daily_limit(FakeClock::default(), MemoryStore::default());
+
+    harness.work_for(90 * MINUTE);
+    harness.take_reminders();
+
+    assert_eq!(harness.notifier.screen_time.active, 90 * MINUTE);
+    assert_eq!(harness.screen_times.last(), Some(&(90 * MINUTE)));
+    assert_eq!(harness.screen_times.len(), 90);
+    assert_eq!(
+        harness.screen_time.saved().map(|saved| saved.active),
+        Some(90 * MINUTE)
+    );
+    assert_eq!(
+        harness.notifier.describe_screen_time(),
+        "Screen time today: 1h 30m of 8h 00m"
+    );
+}
+
+#[test]
+fn time_away_is_not_screen_time() {
+    let mut harness = daily_limit(FakeClock::default(), MemoryStore::default());
+
+    harness.work_for(10 * MINUTE);
+
+    // Away from 10 to 25 minutes, the time before being detected as away still counts.
+    harness.run_for(15 * MINUTE);
+    assert_eq!(harness.notifier.screen_time.active, 14 * MINUTE);
+
+    harness.work_for(5 * MINUTE);
+    assert_eq!(harness.notifier.screen_time.active, 19 * MINUTE);
+}
+
+#[test]
+fn daily_limit_notifies_at_budget_then_more_often() {
+    let mut harness = daily_limit(FakeClock::default(), MemoryStore::default());
+
+    harness.work_for(8 * 60 * MINUTE - MINUTE);
+    assert!(daily_limits(&mut harness).is_empty());
+    assert_eq!(
+        harness.notifier.next_daily_limit(),
+        Some(harness.clock.now() + MINUTE)
+    );
+
+    harness.work_for(MINUTE);
+    assert_eq!(daily_limits(&mut harness), [8 * 60 * MINUTE]);
+
+    // Each gap is half of the last, down to ten minutes.
+    harness.work_for(2 * 60 * MINUTE + 10 * MINUTE);
+    assert_eq!(
+        daily_limits(&mut harness),
+        [480, 540, 570, 585, 595, 605].map(|minutes| minutes * MINUTE)
+    );
+}
+
+#[test]
+fn screen_time_resets_on_a_new_day() {
+    let clock = FakeClock::at(date(1).and_time(time(23, 0)));
+    let mut harness = daily_limit(clock, MemoryStore::default());
+
+    harness.work_for(59 * MINUTE);
+    assert_eq!(harness.notifier.screen_time.date, date(1));
+    assert_eq!(harness.notifier.screen_time.active, 59 * MINUTE);
+
+    // Only the time after midnight counts towards the new day.
+    harness.work_for(31 * MINUTE);
+    assert_eq!(harness.notifier.screen_time.date, date(2));
+    assert_eq!(harness.notifier.screen_time.active, 30 * MINUTE);
+}
+
+#[test]
+fn screen_time_survives_a_restart() {
+    let clock = FakeClock::at(date(1).and_time(time(9, 0)));
+    let store = MemoryStore::default();
+    let mut harness = daily_limit(clock.clone(), store.clone());
+
+    harness.work_for(8 * 60 * MINUTE);
+    assert_eq!(daily_limits(&mut harness).len(), 1);
+
+    // Restarted later the same day, the total and the notifications sent carry on.
+    clock.advance(30 * MINUTE);
+    let mut harness = daily_limit(clock.clone(), store.clone());
+    assert_eq!(harness.notifier.screen_time.active, 8 * 60 * MINUTE);
+
+    harness.work_for(60 * MINUTE);
+    assert_eq!(daily_limits(&mut harness), [9 * 60 * MINUTE]);
+
+    // Restarted the next day, the saved total is from another day.
+    clock.advance(12 * 60 * MINUTE);
+    let harness = daily_limit(clock, store);
+    assert_eq!(harness.notifier.screen_time.active, Duration::ZERO);
+    assert_eq!(harness.notifier.screen_time.date, date(2));
+}
+
+fn aligned(hour: u32, minute: u32, offset: Duration) -> Harness {
+    let config = base_config(vec![ScheduleConfig {
+        alignment: Some(AlignmentConfig {
+            offset,
+            ..Default::default()
+        }),
+        ..schedule("Break", 30 * MINUTE, 5 * MINUTE)
+    }]);
+    Harness::with_config(&config, FakeClock::at(date(1).and_time(time(hour, minute))))
+}
+
+#[test]
+fn aligned_reminders_fall_on_the_clock() {
+    // Starting at 09:07, the first reminder waits for 09:30.
+    let mut harness = aligned(9, 7, Duration::ZERO);
+
+    assert!(harness.run_for(22 * MINUTE).is_empty());
+    let reminders = harness.run_for(MINUTE);
+    assert_eq!(reminders.len(), 1);
+    assert_eq!(harness.clock.local_now(), date(1).and_time(time(9, 30)));
+
+    // Ended on time, the next reminder is at 10:00 rather than 30 minutes after the break.
+    harness.run_for(5 * MINUTE);
+    harness.end_break(reminders[0].break_id);
+    assert!(harness.run_for(24 * MINUTE).is_empty());
+    assert_eq!(harness.run_for(MINUTE).len(), 1);
+    assert_eq!(harness.clock.local_now(), date(1).and_time(time(10, 0)));
+}
+
+#[test]
+fn late_break_skips_slots_that_are_too_close() {
+    let mut harness = aligned(9, 0, Duration::ZERO);
+
+    let reminders = harness.run_for(30 * MINUTE);
+    assert_eq!(reminders.len(), 1);
+
+    // Ended at 09:52, 10:00 is within the minimum gap so the reminder waits for 10:30.
+    harness.run_for(22 * MINUTE);
+    harness.end_break(reminders[0].break_id);
+    assert!(harness.run_for(37 * MINUTE).is_empty());
+    assert_eq!(harness.run_for(MINUTE).len(), 1);
+    assert_eq!(harness.clock.local_now(), date(1).and_time(time(10, 30)));
+}
+
+#[test]
+fn starting_just_before_a_slot_waits_for_the_next() {
+    let mut harness = aligned(9, 25, Duration::ZERO);
+
+    assert!(harness.run_for(34 * MINUTE).is_empty());
+    assert_eq!(harness.run_for(MINUTE).len(), 1);
+    assert_eq!(harness.clock.local_now(), date(1).and_time(time(10, 0)));
+}
+
+#[test]
+fn aligned_slots_can_be_offset() {
+    let mut harness = aligned(9, 0, 5 * MINUTE);
+
+    assert!(harness.run_for(34 * MINUTE).is_empty());
+    assert_eq!(harness.run_for(MINUTE).len(), 1);
+    assert_eq!(harness.clock.local_now(), date(1).and_time(time(9, 35)));
+    assert!(harness.notifier.schedules[0]
+        .describe(&harness.clock)
+        .contains("Aligned to the clock with a 5 minute offset"));
+}
+
+fn timed(day: u32, hour: u32, minute: u32, timed: ScheduleConfig) -> Harness {
+    let config = base_config(vec![schedule("Break", 20 * MINUTE, 5 * MINUTE), timed]);
+    Harness::with_config(
+        &config,
+        FakeClock::at(date(day).and_time(time(hour, minute))),
+    )
+}
+
+fn lunch() -> ScheduleConfig {
+    ScheduleConfig {
+        message: "Lunch time.".to_string(),
+        times: vec![time(12, 30)],
+        ..schedule("Lunch", Duration::ZERO, 5 * MINUTE)
+    }
+}
+
+#[test]
+fn fixed_time_reminder_is_sent_at_its_time() {
+    let mut harness = timed(1, 12, 0, lunch());
+
+    let reminders = harness.run_for(20 * MINUTE);
+    assert_eq!(reminders.len(), 1);
+    assert_eq!(reminders[0].schedule, "Break");
+
+    // A break ending just before does not cover the fixed time reminder.
+    harness.run_for(9 * MINUTE);
+    harness.end_break(reminders[0].break_id);
+    let reminders = harness.run_for(MINUTE);
+    assert_eq!(reminders.len(), 1);
+    assert_eq!(reminders[0].schedule, "Lunch");
+    assert_eq!(reminders[0].message, "Lunch time.");
+
+    harness.end_break(reminders[0].break_id);
+    let description = harness.notifier.schedules[1].describe(&harness.clock);
+    assert!(description.starts_with("Lunch: at 12:30, "));
+    assert!(description.ends_with("Next reminder at Tue 12:30"));
+}
+
+#[test]
+fn fixed_time_reminder_waits_for_pause_to_end() {
+    let mut harness = timed(1, 12, 0, lunch());
+
+    harness.send(Message::PauseReminders(PauseLength::Until(time(13, 0))));
+    assert!(harness.run_for(59 * MINUTE).is_empty());
+
+    // Both the interval and the lunch reminder are overdue, with breaks of the same length the
+    // first schedule is sent and the lunch follows once it ends.
+    let reminders = harness.run_for(MINUTE);
+    assert_eq!(reminders.len(), 1);
+    assert_eq!(reminders[0].schedule, "Break");
+    harness.end_break(reminders[0].break_id);
+    let reminders = harness.take_reminders();
+    assert_eq!(reminders.len(), 1);
+    assert_eq!(reminders[0].schedule, "Lunch");
+}
+
+#[test]
+fn cron_reminders_follow_the_expression() {
+    // Friday 13:30, every two hours on weekdays.
+    let mut harness = timed(
+        5,
+        13,
+        30,
+        ScheduleConfig {
+            cron: Some("0 */2 * * 1-5".parse().unwrap()),
+            ..schedule("Walk", Duration::ZERO, 10 * MINUTE)
+        },
+    );
+    harness.notifier.schedules.remove(0);
+
+    assert!(harness.run_for(29 * MINUTE).is_empty());
+    let reminders = harness.run_for(MINUTE);
+    assert_eq!(reminders.len(), 1);
+    harness.end_break(reminders[0].break_id);
+
+    let walk = &harness.notifier.schedules[0];
+    let description = walk.describe(&harness.clock);
+    assert!(description.starts_with("Walk: on \"0 */2 * * 1-5\", "));
+    assert!(description.ends_with("Next reminder at Fri 16:00"));
+
+    // After the last one on Friday, the next is at midnight on Monday.
+    let trigger = walk.trigger.as_ref().unwrap();
+    assert_eq!(
+        trigger.next_after(date(5).and_time(time(22, 0))),
+        Some(date(8).and_time(time(0, 0)))
+    );
+}
+
+/// A day in 2024.
+fn date_in(month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, month, day).unwrap()
+}
+
+/// Starts on the morning the clocks go forward from 01:00 to 02:00.
+fn spring_forward(hour: u32, minute: u32) -> FakeClock {
+    let clock = FakeClock::at(date_in(3, 31).and_time(time(hour, minute)));
+    clock.change_offset(date_in(3, 31).and_time(time(1, 0)), 1);
+    clock
+}
+
+#[test]
+fn pause_until_a_skipped_time_ends_when_the_clocks_go_forward() {
+    let clock = spring_forward(0, 30);
+    let mut harness = Harness::with_config(&Config::default(), clock);
+
+    // 01:30 never happens, the pause ends at 02:00, 30 minutes later.
+    harness.send(Message::PauseReminders(PauseLength::Until(time(1, 30))));
+    assert_eq!(
+        harness.notifier.pause_end(),
+        Some(harness.clock.now() + 30 * MINUTE)
+    );
+
+    harness.run_for(29 * MINUTE);
+    assert!(harness.notifier.is_paused());
+    harness.run_for(MINUTE);
+    assert!(!harness.notifier.is_paused());
+    assert_eq!(
+        harness.clock.local_now(),
+        date_in(3, 31).and_time(time(2, 0))
+    );
+}
+
+#[test]
+fn pause_until_a_repeated_time_ends_at_the_next_one() {
+    // 01:30 before the clocks go back from 02:00 to 01:00.
+    let clock = FakeClock::at(date_in(10, 27).and_time(time(0, 30)));
+    clock.change_offset(date_in(1, 1).and_time(time(0, 0)), 1);
+    clock.change_offset(date_in(10, 27).and_time(time(2, 0)), 0);
+    let mut harness = Harness::with_config(&Config::default(), clock);
+    assert_eq!(
+        harness.clock.local_now(),
+        date_in(10, 27).and_time(time(1, 30))
+    );
+
+    // 01:15 has passed once but happens again after the clocks go back.
+    harness.send(Message::PauseReminders(PauseLength::Until(time(1, 15))));
+    harness.run_for(44 * MINUTE);
+    assert!(harness.notifier.is_paused());
+    harness.run_for(MINUTE);
+    assert!(!harness.notifier.is_paused());
+    assert_eq!(
+        harness.clock.local_now(),
+        date_in(10, 27).and_time(time(1, 15))
+    );
+}
+
+#[test]
+fn fixed_time_reminder_is_on_time_after_the_clocks_go_forward() {
+    let mut harness = Harness::with_config(&base_config(vec![lunch()]), spring_forward(0, 0));
+
+    // Only 11 and a half hours until 12:30 as an hour is skipped.
+    assert!(harness.run_for(11 * 60 * MINUTE + 29 * MINUTE).is_empty());
+    assert_eq!(harness.run_for(MINUTE).len(), 1);
+    assert_eq!(
+        harness.clock.local_now(),
+        date_in(3, 31).and_time(time(12, 30))
+    );
+}
+
+#[test]
+fn time_zone_change_updates_the_pause_end_shown() {
+    let mut harness = Harness::new(20 * MINUTE);
+    harness.clock.advance(10 * 60 * MINUTE);
+
+    harness.send(Message::PauseReminders(PauseLength::For(60 * MINUTE)));
+
+    // Moving two hours east, the pause still lasts an hour but ends at 13:00 local time.
+    harness.clock.change_offset(harness.clock.local_now(), 2);
+    harness.step();
+    harness.take_reminders();
+    assert!(matches!(
+        harness.other_events.last(),
+        Some(NotifierEvent::Paused(Some(resumes))) if *resumes == date(1).and_time(time(13, 0))
+    ));
+
+    harness.run_for(59 * MINUTE);
+    assert!(harness.notifier.is_paused());
+    harness.run_for(MINUTE);
+    assert!(!harness.notifier.is_paused());
+}
+
+fn day_off(name: &str, start: NaiveDate, end: Option<NaiveDate>, yearly: bool) -> DayOff {
+    DayOff {
+        name: name.to_string(),
+        start,
+        end,
+        yearly,
+    }
+}
+
+fn days_off(clock: FakeClock, days: Vec<DayOff>) -> Harness {
+    let config = Config {
+        days_off: DaysOff { days },
+        ..base_config(vec![schedule("Break", 20 * MINUTE, 5 * MINUTE)])
+    };
+    Harness::with_config(&config, clock)
+}
+
+#[test]
+fn no_reminders_on_a_day_off() {
+    let clock = FakeClock::at(date(2).and_time(time(0, 0)));
+    let mut harness = days_off(clock, vec![day_off("Holiday", date(2), None, false)]);
+
+    assert!(harness.run_for(24 * 60 * MINUTE - MINUTE).is_empty());
+    assert_eq!(
+        harness.notifier.next_deadline(),
+        Some(harness.clock.now() + MINUTE)
+    );
+
+    // The timer restarts at midnight when the day off ends.
+    assert!(harness.run_for(20 * MINUTE).is_empty());
+    assert_eq!(harness.run_for(MINUTE).len(), 1);
+    assert_eq!(harness.clock.local_now(), date(3).and_time(time(0, 20)));
+}
+
+#[test]
+fn days_off_can_be_a_range() {
+    let clock = FakeClock::at(date(5).and_time(time(23, 30)));
+    let vacation = day_off("Vacation", date(6), Some(date(14)), false);
+    let mut harness = days_off(clock, vec![vacation]);
+
+    let reminders = harness.run_for(20 * MINUTE);
+    assert_eq!(reminders.len(), 1);
+    harness.end_break(reminders[0].break_id);
+
+    // From 23:50 until the end of the vacation.
+    assert!(harness
+        .run_for(10 * MINUTE + 9 * 24 * 60 * MINUTE)
+        .is_empty());
+    assert_eq!(harness.clock.local_now(), date(15).and_time(time(0, 0)));
+    assert!(!harness.notifier.is_day_off());
+
+    assert_eq!(harness.run_for(20 * MINUTE).len(), 1);
+}
+
+#[test]
+fn yearly_days_off_can_run_over_the_new_year() {
+    let december = |day| NaiveDate::from_ymd_opt(2024, 12, day).unwrap();
+    let holidays = DaysOff {
+        days: vec![day_off(
+            "Christmas holidays",
+            NaiveDate::from_ymd_opt(2019, 12, 24).unwrap(),
+            Some(NaiveDate::from_ymd_opt(2020, 1, 2).unwrap()),
+            true,
+        )],
+    };
+
+    assert!(holidays.get(december(23)).is_none());
+    assert!(holidays.get(december(24)).is_some());
+    assert!(holidays.get(december(31)).is_some());
+    assert!(holidays.get(date(2)).is_some());
+    assert!(holidays.get(date(3)).is_none());
+    assert!(holidays.invalid_day().is_none());
+}
+
+#[test]
+fn suppression_reason_is_sent_when_it_changes() {
+    let clock = FakeClock::at(date(1).and_time(time(10, 0)));
+    let mut harness = days_off(clock, vec![day_off("Holiday", date(2), None, false)]);
+
+    harness.step();
+    assert_eq!(
+        harness.notifier.describe_suppression(),
+        "Reminders suppressed: No"
+    );
+
+    harness.send(Message::PauseReminders(PauseLength::UntilResumed));
+    harness.run_for(MINUTE);
+    harness.send(Message::Resume);
+
+    // The day off is reported from midnight.
+    harness.run_for(14 * 60 * MINUTE);
+    assert_eq!(
+        harness.notifier.describe_suppression(),
+        "Reminders suppressed: Day off: Holiday"
+    );
+
+    assert_eq!(
+        harness.suppressed,
+        [
+            Some("Paused until resumed".to_string()),
+            None,
+            Some("Day off: Holiday".to_string()),
+        ]
+    );
+}
+
+fn interval_range(start: NaiveTime, end: NaiveTime, interval: Duration) -> IntervalRange {
+    IntervalRange {
+        start,
+        end,
+        interval,
+    }
+}
+
+/// A 20 minute schedule with 45 minutes in the morning and 30 in the afternoon.
+fn interval_curve(hour: u32, minute: u32) -> Harness {
+    let config = base_config(vec![ScheduleConfig {
+        curve: vec![
+            interval_range(time(9, 0), time(12, 0), 45 * MINUTE),
+            interval_range(time(15, 0), time(18, 0), 30 * MINUTE),
+        ],
+        ..schedule("Break", 20 * MINUTE, 5 * MINUTE)
+    }]);
+    Harness::with_config(&config, FakeClock::at(date(1).and_time(time(hour, minute))))
+}
+
+#[test]
+fn interval_follows_the_time_of_day() {
+    let mut harness = interval_curve(9, 0);
+
+    assert!(harness.run_for(44 * MINUTE).is_empty());
+    let reminders = harness.run_for(MINUTE);
+    assert_eq!(reminders.len(), 1);
+    harness.end_break(reminders[0].break_id);
+
+    // Outside of every range the schedule's own interval applies.
+    harness.clock.advance(3 * 60 * MINUTE);
+    harness.notifier.schedules[0].restart(harness.clock.now());
+    assert_eq!(harness.clock.local_now(), date(1).and_time(time(12, 45)));
+    assert!(harness.run_for(19 * MINUTE).is_empty());
+    assert_eq!(harness.run_for(MINUTE).len(), 1);
+}
+
+#[test]
+fn interval_crossing_into_another_range_counts_the_time_in_each() {
+    let mut harness = interval_curve(14, 50);
+
+    // Half of the 20 minute interval passes before 15:00, the other half is 15 of 30 minutes.
+    assert_eq!(
+        harness.notifier.schedules[0].next_due(&harness.clock),
+        Some(harness.clock.now() + 25 * MINUTE)
+    );
+    assert!(harness.run_for(24 * MINUTE).is_empty());
+    assert_eq!(harness.run_for(MINUTE).len(), 1);
+    assert_eq!(harness.clock.local_now(), date(1).and_time(time(15, 15)));
+}
+
+#[test]
+fn interval_curve_is_described() {
+    let harness = interval_curve(15, 30);
+
+    let description = harness.notifier.schedules[0].describe(&harness.clock);
+    assert!(description.starts_with("Break: every 30 minutes, "));
+    assert!(description.contains("Next reminder at Mon 16:00"));
+    assert!(description.ends_with(
+        "Interval curve: 45 minutes from 09:00 to 12:00, 30 minutes from 15:00 to 18:00"
+    ));
+}
+
+fn break_debt(debt: BreakDebtConfig, resume: ResumeBehaviour) -> Harness {
+    let config = Config {
+        resume,
+        break_debt: Some(debt),
+        ..base_config(vec![schedule("Break", 20 * MINUTE, 5 * MINUTE)])
+    };
+    Harness::with_config(&config, FakeClock::default())
+}
+
+fn owed(harness: &Harness) -> Duration {
+    harness.notifier.break_debt.as_ref().unwrap().owed
+}
+
+#[test]
+fn skipped_break_time_is_owed_then_repaid() {
+    let mut harness = break_debt(BreakDebtConfig::default(), ResumeBehaviour::default());
+
+    let reminders = harness.run_for(20 * MINUTE);
+    harness.end_break(reminders[0].break_id);
+    assert_eq!(owed(&harness), 5 * MINUTE);
+
+    // The next break is made longer by the time owed.
+    let reminders = harness.run_for(20 * MINUTE);
+    assert_eq!(reminders[0].length, 10 * MINUTE);
+    assert!(reminders[0]
+        .message
+        .ends_with("This break is 5 minutes longer to make up for missed breaks."));
+
+    harness.run_for(10 * MINUTE);
+    harness.end_break(reminders[0].break_id);
+    assert_eq!(owed(&harness), Duration::ZERO);
+
+    let description = harness.notifier.describe_break_debt().unwrap();
+    assert!(description.starts_with("Break debt: 0 seconds of at most 30 minutes owed\n"));
+    assert!(description.contains("Break break taken with extra time 0 minutes ago -5 minutes"));
+    assert!(description.contains("Break break skipped 30 minutes ago +5 minutes"));
+}
+
+#[test]
+fn break_debt_and_extensions_are_capped() {
+    let debt = BreakDebtConfig {
+        max_debt: 8 * MINUTE,
+        max_extension: 50,
+    };
+    let mut harness = break_debt(debt, ResumeBehaviour::default());
+
+    let reminders = harness.run_for(20 * MINUTE);
+    harness.end_break(reminders[0].break_id);
+
+    // Half of the break length is repaid at most.
+    let reminders = harness.run_for(20 * MINUTE);
+    assert_eq!(
+        reminders[0].length,
+        7 * MINUTE + 30 * Duration::from_secs(1)
+    );
+
+    // Skipping it again owes its own length on top of what it would have repaid.
+    harness.end_break(reminders[0].break_id);
+    assert_eq!(owed(&harness), 8 * MINUTE);
+}
+
+#[test]
+fn breaks_dropped_by_a_pause_are_owed() {
+    let mut harness = break_debt(BreakDebtConfig::default(), ResumeBehaviour::RestartInterval);
+
+    harness.run_for(10 * MINUTE);
+    harness.send(Message::PauseReminders(PauseLength::For(30 * MINUTE)));
+    assert!(harness.run_for(30 * MINUTE).is_empty());
+
+    let debt = harness.notifier.break_debt.as_ref().unwrap();
+    assert_eq!(debt.owed, 5 * MINUTE);
+    assert_eq!(debt.history.back().unwrap().reason, DebtReason::Paused);
+
+    assert!(harness.run_for(19 * MINUTE).is_empty());
+    let reminders = harness.run_for(MINUTE);
+    assert_eq!(reminders[0].length, 10 * MINUTE);
+}
+
+#[test]
+fn snoozed_break_abandoned_by_a_restart_is_owed() {
+    let config = Config {
+        break_debt: Some(BreakDebtConfig::default()),
+        ..base_config(vec![pomodoro_schedule()])
+    };
+    let mut harness = Harness::with_config(&config, FakeClock::default());
+    harness.send(Message::StartPomodoro(None));
+
+    let reminders = harness.run_for(25 * MINUTE);
+    snooze(&mut harness, reminders[0].break_id);
+    harness.send(Message::StartPomodoro(None));
+
+    let debt = harness.notifier.break_debt.as_ref().unwrap();
+    assert_eq!(debt.owed, 5 * MINUTE);
+    assert_eq!(debt.history.back().unwrap().reason, DebtReason::Expired);
+}
+
+/// A schedule whose breaks scale with the default curve, of 5 minutes after 25 minutes of work
+/// up to 20 minutes after 3 hours.
+fn scaled_breaks(interval: Duration) -> Harness {
+    Harness::with_schedules(vec![ScheduleConfig {
+        break_scaling: Some(BreakScalingConfig::default()),
+        ..schedule("Break", interval, 5 * MINUTE)
+    }])
+}
+
+#[test]
+fn break_length_scales_with_time_worked() {
+    let mut harness = scaled_breaks(25 * MINUTE);
+    let reminders = harness.run_for(25 * MINUTE);
+    assert_eq!(reminders[0].length, 5 * MINUTE);
+    assert!(reminders[0]
+        .message
+        .ends_with("You have worked for 25 minutes, take a break of 5 minutes."));
+
+    let mut harness = scaled_breaks(3 * 60 * MINUTE);
+    let reminders = harness.run_for(3 * 60 * MINUTE);
+    assert_eq!(reminders[0].length, 20 * MINUTE);
+
+    // Past the last point the break keeps growing, up to the longest break.
+    let mut harness = scaled_breaks(8 * 60 * MINUTE);
+    let reminders = harness.run_for(8 * 60 * MINUTE);
+    assert_eq!(reminders[0].length, 30 * MINUTE);
+}
+
+#[test]
+fn scaled_break_length_picks_the_reminder_to_send() {
+    let mut harness = Harness::with_schedules(vec![
+        schedule("Fixed", 3 * 60 * MINUTE, 10 * MINUTE),
+        ScheduleConfig {
+            break_scaling: Some(BreakScalingConfig::default()),
+            ..schedule("Scaled", 3 * 60 * MINUTE, 5 * MINUTE)
+        },
+    ]);
+
+    // After 3 hours the scaled break is 20 minutes, longer than the fixed one it covers.
+    let reminders = harness.run_for(3 * 60 * MINUTE);
+    assert_eq!(reminders.len(), 1);
+    assert_eq!(reminders[0].length, 20 * MINUTE);
+    assert!(harness.notifier.schedules[1].is_outstanding());
+}
+
+#[test]
+fn break_lengths_are_described_in_minutes_or_seconds() {
+    assert_eq!(format_length(30 * Duration::from_secs(1)), "30 seconds");
+    assert_eq!(format_length(90 * Duration::from_secs(1)), "1 minute");
+    assert_eq!(format_length(5 * MINUTE), "5 minutes");
+
+    // Pomodoro breaks keep their own length, so they do not say how long was worked.
+    let mut harness = Harness::with_schedules(vec![ScheduleConfig {
+        break_scaling: Some(BreakScalingConfig::default()),
+        ..pomodoro_schedule()
+    }]);
+    harness.send(Message::StartPomodoro(None));
+    let reminders = harness.run_for(25 * MINUTE);
+    assert!(!reminders[0].message.contains("You have worked for"));
+}
+
+#[test]
+fn scaled_break_counts_work_from_the_last_break() {
+    let mut harness = scaled_breaks(25 * MINUTE);
+    harness.send(Message::PauseReminders(PauseLength::For(56 * MINUTE)));
+
+    // The reminder held back by the pause is sent after 56 minutes of work, 31 minutes past the
+    // first point adds a fifth of the 15 minutes between the points.
+    let reminders = harness.run_for(56 * MINUTE);
+    assert_eq!(reminders[0].length, 8 * MINUTE);
+
+    harness.run_for(8 * MINUTE);
+    harness.end_break(reminders[0].break_id);
+    let reminders = harness.run_for(25 * MINUTE);
+    assert_eq!(reminders[0].length, 5 * MINUTE);
+}
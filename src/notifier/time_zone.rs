@@ -0,0 +1,33 @@
+use tracing::info;
+
+use crate::{calendar, message::NotifierEvent};
+
+use super::{Notifier, ShouldCloseThread};
+
+impl Notifier {
+    /// Checks if the local offset from UTC has changed, from a daylight saving change or the
+    /// time zone being changed. Local times are resolved in the current time zone whenever they
+    /// are used, so only what the event loop shows needs updating.
+    pub(super) fn detect_time_zone_change(&mut self) -> Result<(), ShouldCloseThread> {
+        let offset = calendar::utc_offset(&*self.clock);
+        let last_offset = std::mem::replace(&mut self.utc_offset, offset);
+        if offset == last_offset {
+            return Ok(());
+        }
+
+        info!(
+            "Local time moved from UTC{} to UTC{}",
+            calendar::describe_offset(last_offset),
+            calendar::describe_offset(offset)
+        );
+
+        // A pause of a set length now ends at a different local time.
+        match self.paused.as_ref() {
+            Some(pause) if pause.is_active(&*self.clock) => {
+                let resumes = pause.resumes_local(&*self.clock);
+                self.send_event(NotifierEvent::Paused(resumes))
+            }
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use std::{cmp::Reverse, time::Instant};
+
+use tracing::info;
+
+use crate::{break_reminder::Break, schedule::Schedule};
+
+use super::{Notifier, ShouldCloseThread};
+
+impl Notifier {
+    /// Returns when a reminder will be forced for working too long without a real break, `None`
+    /// if there is no cap, a reminder cannot be forced or there is no schedule to force.
+    pub(super) fn next_forced_reminder(&self) -> Option<Instant> {
+        let cap = self.work_cap.as_ref()?;
+
+        let is_shown = self
+            .schedules
+            .iter()
+            .any(|schedule| schedule.last_break.state().is_shown());
+        let is_snoozed = self.schedules.iter().any(Schedule::is_snoozed);
+        if is_shown
+            || self.is_day_off()
+            || !self.is_working()
+            || self.away_since.is_some()
+            || (self.is_paused() && !cap.override_pause)
+            || (is_snoozed && !cap.override_snooze)
+            || self.forced_schedule().is_none()
+        {
+            return None;
+        }
+
+        let exceeded = self.working_since + cap.max_work;
+        match self.work_cap_forced {
+            Some(forced) => Some(exceeded.max(forced + cap.repeat)),
+            None => Some(exceeded),
+        }
+    }
+
+    /// Returns the index of the schedule a forced reminder is sent for, a snoozed break is sent
+    /// again early, otherwise the schedule with the longest break is sent. Stopped pomodoros and
+    /// schedules with fixed times are never forced.
+    fn forced_schedule(&self) -> Option<usize> {
+        let now = self.clock.now();
+        let snoozed = self.schedules.iter().position(Schedule::is_snoozed);
+
+        snoozed.or_else(|| {
+            self.schedules
+                .iter()
+                .enumerate()
+                .filter(|(_, schedule)| {
+                    schedule.trigger.is_none()
+                        && schedule
+                            .pomodoro
+                            .as_ref()
+                            .is_none_or(|pomodoro| pomodoro.running)
+                })
+                .max_by_key(|(index, schedule)| (schedule.break_length_at(now), Reverse(*index)))
+                .map(|(index, _)| index)
+        })
+    }
+
+    /// Forces a reminder once the cap on continuous work is exceeded. Returns if a reminder was
+    /// forced.
+    pub(super) fn enforce_work_cap(&mut self) -> Result<bool, ShouldCloseThread> {
+        let now = self.clock.now();
+        if self.next_forced_reminder().is_none_or(|due| now < due) {
+            return Ok(false);
+        }
+
+        let Some(index) = self.forced_schedule() else {
+            return Ok(false);
+        };
+
+        let worked = now.saturating_duration_since(self.working_since).as_secs() / 60;
+        info!(
+            "Worked for {worked} minutes without a real break, forcing a {} reminder",
+            self.schedules[index].name
+        );
+        self.work_cap_forced = Some(now);
+
+        let Some(mut reminder) = self.prepare_reminder(index, true) else {
+            return Ok(false);
+        };
+        reminder.message = format!(
+            "You have worked for {worked} minutes without a real break.\n\n{}",
+            reminder.message
+        );
+        self.send_reminder_event(reminder)?;
+
+        Ok(true)
+    }
+
+    /// Ends the stretch of continuous work if the finished break was long enough.
+    pub(super) fn record_real_break(&mut self, finished: &Break) {
+        let Some(cap) = self.work_cap.as_ref() else {
+            return;
+        };
+
+        let taken = finished.taken().unwrap_or_default();
+        if taken < cap.min_break {
+            return;
+        }
+
+        self.working_since = finished.ended().unwrap_or_else(|| self.clock.now());
+        self.work_cap_forced = None;
+    }
+}
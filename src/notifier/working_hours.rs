@@ -0,0 +1,49 @@
+use std::time::Instant;
+
+use tracing::info;
+
+use crate::calendar;
+
+use super::Notifier;
+
+impl Notifier {
+    /// Returns if the current time is within working hours, always `true` without working hours.
+    pub(super) fn is_working(&self) -> bool {
+        self.working_hours
+            .as_ref()
+            .is_none_or(|hours| hours.contains(self.clock.local_now()))
+    }
+
+    /// Returns when the next working window starts, `None` if there is no upcoming window.
+    pub(super) fn next_working_start(&self) -> Option<Instant> {
+        let local = self.clock.local_now();
+        let start = self.working_hours.as_ref()?.next_start(local)?;
+
+        let start = calendar::resolve_after(&*self.clock, start, self.clock.utc_now());
+        Some(calendar::instant_of(&*self.clock, start))
+    }
+
+    /// Restarts every schedule's interval when a new working window has started, so the first
+    /// reminder of the window is a full interval after it starts.
+    pub(super) fn update_working_window(&mut self) {
+        let Some(hours) = self.working_hours.as_ref() else {
+            return;
+        };
+
+        let now = self.clock.now();
+        let local = self.clock.local_now();
+        let window = hours.window_start(local);
+
+        if let Some(start) = window.filter(|_| window != self.working_window) {
+            info!("Working window started at {start}, restarting reminders");
+
+            // The notifier may wake up after the window started, the interval still starts from
+            // the window start.
+            let started = calendar::instant_at(&*self.clock, start).min(now);
+
+            self.restart_schedules(started);
+        }
+
+        self.working_window = window;
+    }
+}
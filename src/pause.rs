@@ -1,32 +1,85 @@
-use std::{
-    fmt::Display,
-    time::{Duration, Instant},
-};
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDateTime, NaiveTime, Utc};
+
+use crate::{calendar, clock::Clock};
+
+/// How long reminders should be paused for.
+#[derive(Clone, Copy, Debug)]
+pub enum PauseLength {
+    /// Pause for a fixed duration.
+    For(Duration),
+
+    /// Pause until the next time the clock reads this time of day.
+    Until(NaiveTime),
+
+    /// Pause until the first working window tomorrow or later, or tomorrow morning without
+    /// working hours.
+    TomorrowMorning,
+
+    /// Pause until reminders are resumed.
+    UntilResumed,
+}
+
+/// When a pause ends.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PauseEnd {
+    /// A fixed moment, for a pause of a set length.
+    At(DateTime<Utc>),
+
+    /// A local time, which stays the same time of day if the time zone changes.
+    Local(NaiveDateTime),
+}
 
 /// Details about an *ongoing* or *previous* pause.
 pub struct Pause {
-    /// How long the pause should last for.
-    pub duration: Duration,
-
     /// When the pause was started.
-    pub started: Instant,
+    pub started: DateTime<Utc>,
+
+    /// When the pause ends, `None` if it lasts until reminders are resumed.
+    pub resumes: Option<PauseEnd>,
 }
 
 impl Pause {
-    /// If the pause is ongoing or not.
-    pub fn is_active(&self) -> bool {
-        self.started.elapsed() < self.duration
+    /// Returns the moment the pause ends in the current time zone, `None` if it lasts until
+    /// reminders are resumed.
+    pub fn resumes_at(&self, clock: &dyn Clock) -> Option<DateTime<Utc>> {
+        match self.resumes? {
+            PauseEnd::At(utc) => Some(utc),
+            PauseEnd::Local(local) => Some(calendar::resolve_after(clock, local, self.started)),
+        }
+    }
+
+    /// Returns the local time the pause ends in the current time zone, `None` if it lasts until
+    /// reminders are resumed.
+    pub fn resumes_local(&self, clock: &dyn Clock) -> Option<NaiveDateTime> {
+        match self.resumes? {
+            PauseEnd::At(utc) => Some(clock.local_at(utc)),
+            PauseEnd::Local(local) => Some(local),
+        }
     }
-}
 
-impl Display for Pause {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "Pause {{ duration: {} minutes, started: {} minutes ago, is_active: {} }}",
-            self.duration.as_secs() / 60,
-            self.started.elapsed().as_secs() / 60,
-            self.is_active()
+    /// If the pause is ongoing at the clock's current time or not.
+    pub fn is_active(&self, clock: &dyn Clock) -> bool {
+        self.resumes_at(clock)
+            .is_none_or(|resumes| clock.utc_now() < resumes)
+    }
+
+    /// Describes the pause relative to the clock's current time.
+    pub fn describe(&self, clock: &dyn Clock) -> String {
+        format!(
+            "Pause {{ started: {}, until: {}, is_active: {} }}",
+            clock.local_at(self.started).format("%a %H:%M"),
+            describe_resume_time(self.resumes_local(clock)),
+            self.is_active(clock)
         )
     }
 }
+
+/// Describes when a pause resumes, e.g. "Tue 14:00", or "resumed" if it lasts until resumed.
+pub fn describe_resume_time(resumes: Option<NaiveDateTime>) -> String {
+    match resumes {
+        Some(resumes) => resumes.format("%a %H:%M").to_string(),
+        None => "resumed".to_string(),
+    }
+}
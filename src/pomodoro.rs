@@ -0,0 +1,125 @@
+use std::time::{Duration, Instant};
+
+use crate::{break_reminder::minutes_since, config::PomodoroConfig};
+
+/// Pomodoro state for a schedule, the schedule's interval is the length of a work period.
+pub struct Pomodoro {
+    /// The length of the break after most work periods.
+    pub short_break: Duration,
+
+    /// The length of the break after every `long_break_every` work periods.
+    pub long_break: Duration,
+
+    /// How many work periods there are per long break.
+    pub long_break_every: u32,
+
+    /// If work periods are being timed, a stopped pomodoro never sends reminders.
+    pub running: bool,
+
+    /// The number of work periods completed since the last reset.
+    pub cycle: u32,
+
+    /// The label of the task for the current work period.
+    pub task: Option<String>,
+
+    /// The completed work periods since the last reset.
+    pub history: Vec<PomodoroCycle>,
+}
+
+/// A completed pomodoro work period.
+pub struct PomodoroCycle {
+    /// The number of the work period since the last reset, starting at 1.
+    pub cycle: u32,
+
+    /// The label of the task worked on, if one was given.
+    pub task: Option<String>,
+
+    /// When the work period was completed.
+    pub completed: Instant,
+}
+
+impl Pomodoro {
+    /// Constructs a stopped pomodoro.
+    pub fn new(config: &PomodoroConfig) -> Self {
+        Self {
+            short_break: config.short_break,
+            long_break: config.long_break,
+            long_break_every: config.long_break_every.max(1),
+            running: false,
+            cycle: 0,
+            task: None,
+            history: Vec::new(),
+        }
+    }
+
+    /// The length of the break that follows the current work period.
+    pub fn next_break_length(&self) -> Duration {
+        if (self.cycle + 1).is_multiple_of(self.long_break_every) {
+            self.long_break
+        } else {
+            self.short_break
+        }
+    }
+
+    /// Starts timing work periods with an optional task label.
+    pub fn start(&mut self, task: Option<String>) {
+        self.running = true;
+        self.task = task;
+    }
+
+    /// Stops timing and clears the cycle counter and history.
+    pub fn reset(&mut self) {
+        *self = Self {
+            short_break: self.short_break,
+            long_break: self.long_break,
+            long_break_every: self.long_break_every,
+            running: false,
+            cycle: 0,
+            task: None,
+            history: Vec::new(),
+        };
+    }
+
+    /// Records the current work period as completed at `now`, the task label carries on into the
+    /// next work period.
+    pub fn complete_cycle(&mut self, now: Instant) -> &PomodoroCycle {
+        self.cycle += 1;
+        self.history.push(PomodoroCycle {
+            cycle: self.cycle,
+            task: self.task.clone(),
+            completed: now,
+        });
+
+        self.history.last().unwrap()
+    }
+
+    /// Describes the pomodoro relative to `now`.
+    pub fn describe(&self, now: Instant) -> String {
+        let task = match self.task.as_ref() {
+            Some(task) => format!("\"{task}\""),
+            None => "None".to_string(),
+        };
+        let mut description = format!(
+            "Pomodoro {{ running: {}, cycle: {}, long break every: {}, task: {} }}",
+            self.running, self.cycle, self.long_break_every, task
+        );
+
+        for cycle in &self.history {
+            description.push_str(&format!("\n{}", cycle.describe(now)));
+        }
+
+        description
+    }
+}
+
+impl PomodoroCycle {
+    /// Describes the cycle relative to `now`.
+    pub fn describe(&self, now: Instant) -> String {
+        format!(
+            "  Cycle {}: {}, completed {} minutes ago",
+            self.cycle,
+            self.task.as_deref().unwrap_or("No task"),
+            minutes_since(self.completed, now)
+        )
+    }
+}
@@ -0,0 +1,80 @@
+use std::{fs, io, path::Path, time::Duration};
+
+use serde::Deserialize;
+use thiserror::Error;
+
+const ROUTINES_PATH: &str = "./break-routines.toml";
+
+/// The guided break routines, loaded from `break-routines.toml` next to the config file.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Routines {
+    /// The routines schedules can walk through during their breaks.
+    pub routines: Vec<Routine>,
+}
+
+/// A named sequence of steps to walk through during a break.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Routine {
+    /// The name schedules refer to the routine by.
+    pub name: String,
+
+    /// The steps in order.
+    pub steps: Vec<RoutineStep>,
+}
+
+/// A single timed step of a routine.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RoutineStep {
+    /// What to do during the step.
+    pub text: String,
+
+    /// How long the step lasts before moving on to the next one.
+    #[serde(with = "humantime_serde")]
+    pub length: Duration,
+}
+
+impl Routines {
+    /// Loads the routines file, falling back to no routines if it does not exist.
+    pub fn load() -> Result<Self, Error> {
+        let path = Path::new(ROUTINES_PATH);
+
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(Error::Read(e)),
+        };
+
+        let routines: Self = toml::from_str(&contents)?;
+
+        if let Some(routine) = routines
+            .routines
+            .iter()
+            .find(|routine| routine.steps.is_empty())
+        {
+            return Err(Error::NoSteps(routine.name.clone()));
+        }
+
+        Ok(routines)
+    }
+
+    /// Returns the routine with the given name.
+    pub fn get(&self, name: &str) -> Option<&Routine> {
+        self.routines.iter().find(|routine| routine.name == name)
+    }
+}
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("Failed to read break routines:\n{0}")]
+    Read(#[source] io::Error),
+
+    #[error("Failed to parse break routines:\n{0}")]
+    Parse(#[from] toml::de::Error),
+
+    #[error("Break routine \"{0}\" must have at least one step")]
+    NoSteps(String),
+}
@@ -0,0 +1,427 @@
+use std::time::{Duration, Instant};
+
+use chrono::NaiveDateTime;
+use tracing::info;
+
+use crate::{
+    adaptive::Adaptive,
+    alignment::Alignment,
+    break_reminder::{self, format_length, Break, BreakRoutine, BreakState, Reminder},
+    break_scaling::BreakScaling,
+    calendar,
+    clock::Clock,
+    config::{EscalationConfig, EscalationStep, ScheduleConfig},
+    curve::IntervalCurve,
+    pomodoro::Pomodoro,
+    routine::{Routine, Routines},
+    trigger::Trigger,
+};
+
+/// A named reminder schedule with its own interval and break state.
+pub struct Schedule {
+    /// The name of the schedule.
+    pub name: String,
+
+    /// The interval between a break ending and the next reminder.
+    pub interval: Duration,
+
+    /// How long a break for this schedule lasts.
+    pub break_length: Duration,
+
+    /// How the break length scales with the time worked before it, `None` for a fixed length.
+    pub break_scaling: Option<BreakScaling>,
+
+    /// The reminder text, used when no catalogue message was picked.
+    pub message: String,
+
+    /// The tags catalogue messages are filtered by.
+    pub message_tags: Vec<String>,
+
+    /// If the reminder closes itself once the break is over.
+    pub auto_close: bool,
+
+    /// If the reminder cannot be dismissed until the break is over.
+    pub enforce_break_length: bool,
+
+    /// How long snoozing the reminder postpones the break for.
+    pub snooze_length: Duration,
+
+    /// How many times a single break can be snoozed.
+    pub max_snoozes: u32,
+
+    /// The routine to walk through during each break, if any.
+    pub routine: Option<Routine>,
+
+    /// How a reminder left open is escalated, `None` to never escalate.
+    pub escalation: Option<EscalationConfig>,
+
+    /// The adaptive interval state if the interval adapts to how breaks are taken.
+    pub adaptive: Option<Adaptive>,
+
+    /// How the interval changes with the time of day, `None` if it does not.
+    pub curve: Option<IntervalCurve>,
+
+    /// The clock slots reminders are sent at, `None` to send them an interval after the last
+    /// break.
+    pub alignment: Option<Alignment>,
+
+    /// The fixed times reminders are sent at instead of every interval, `None` to use the
+    /// interval.
+    pub trigger: Option<Trigger>,
+
+    /// The pomodoro state if the schedule runs as a pomodoro timer.
+    pub pomodoro: Option<Pomodoro>,
+
+    /// The last break for this schedule.
+    pub last_break: Break,
+}
+
+impl Schedule {
+    /// Constructs a schedule whose first interval starts at `now`, its routine is looked up in
+    /// `routines`.
+    pub fn new(config: &ScheduleConfig, routines: &Routines, now: Instant) -> Self {
+        Self {
+            name: config.name.clone(),
+            interval: config.interval,
+            break_length: config.break_length,
+            break_scaling: config.break_scaling.as_ref().map(BreakScaling::new),
+            message: config.message.clone(),
+            message_tags: config.message_tags.clone(),
+            auto_close: config.auto_close,
+            enforce_break_length: config.enforce_break_length,
+            snooze_length: config.snooze_length,
+            max_snoozes: config.max_snoozes,
+            routine: config
+                .routine
+                .as_ref()
+                .and_then(|name| routines.get(name))
+                .cloned(),
+            escalation: config.escalation.clone(),
+            adaptive: config
+                .adaptive
+                .as_ref()
+                .map(|adaptive| Adaptive::new(adaptive, config.interval)),
+            curve: IntervalCurve::new(&config.curve),
+            alignment: config.alignment.as_ref().map(Alignment::new),
+            trigger: Trigger::new(&config.times, config.cron.as_ref()),
+            pomodoro: config.pomodoro.as_ref().map(Pomodoro::new),
+            last_break: Break::scheduled(now),
+        }
+    }
+
+    /// Returns when the next reminder is due, `None` while a break is outstanding or a pomodoro
+    /// is stopped. A snoozed break is due again when its snooze ends. Schedules that follow the
+    /// clock find their next time in the clock's time zone.
+    pub fn next_due(&self, clock: &dyn Clock) -> Option<Instant> {
+        if self.is_outstanding() {
+            return self.last_break.snoozed_until();
+        }
+
+        if self
+            .pomodoro
+            .as_ref()
+            .is_some_and(|pomodoro| !pomodoro.running)
+        {
+            return None;
+        }
+
+        // The interval runs from when the last break ended, or from when the schedule was last
+        // restarted.
+        let from = self.last_break.changed();
+
+        let from_local = calendar::local_at(clock, from);
+        let due_local = match (
+            self.trigger.as_ref(),
+            self.alignment.as_ref(),
+            self.curve.as_ref(),
+        ) {
+            (Some(trigger), _, _) => trigger.next_after(from_local)?,
+            (None, Some(alignment), _) => {
+                alignment.next_due(from_local, self.interval_at(from_local))
+            }
+            (None, None, Some(curve)) => curve.next_due(from_local, self.interval),
+            (None, None, None) => return Some(from + self.interval),
+        };
+
+        let due = calendar::resolve_after(clock, due_local, calendar::utc_of(clock, from));
+        Some(calendar::instant_of(clock, due))
+    }
+
+    /// Returns the interval at the local time, which only differs from `interval` with a curve.
+    pub fn interval_at(&self, local: NaiveDateTime) -> Duration {
+        match self.curve.as_ref() {
+            Some(curve) => curve.interval_at(local.time(), self.interval),
+            None => self.interval,
+        }
+    }
+
+    /// Returns if the interval has passed at the clock's current time.
+    pub fn is_due(&self, clock: &dyn Clock) -> bool {
+        self.next_due(clock).is_some_and(|due| clock.now() >= due)
+    }
+
+    /// Returns if a reminder has been sent and the break has not ended.
+    pub fn is_outstanding(&self) -> bool {
+        self.last_break.state().is_outstanding()
+    }
+
+    /// Returns if a reminder has been sent and the break has been snoozed.
+    pub fn is_snoozed(&self) -> bool {
+        self.last_break.snoozed_until().is_some()
+    }
+
+    /// Snoozes the outstanding break from `now`, returns `false` without snoozing if the break
+    /// has reached the snooze limit.
+    pub fn snooze(&mut self, now: Instant) -> Result<bool, break_reminder::Error> {
+        if self.last_break.snoozes >= self.max_snoozes {
+            return Ok(false);
+        }
+
+        self.last_break.snooze(now, now + self.snooze_length)?;
+        self.last_break.snoozes += 1;
+        Ok(true)
+    }
+
+    /// Returns when the open reminder reaches its next escalation step, `None` if it is not open
+    /// or has no steps left. The event loop acknowledges and starts a break as soon as it shows
+    /// the reminder, so the reminder keeps escalating until the user ends or snoozes it.
+    pub fn next_escalation(&self) -> Option<Instant> {
+        let escalation = self.escalation.as_ref()?;
+        if !self.last_break.state().is_shown() {
+            return None;
+        }
+
+        let reached = self.last_break.escalations.len();
+        if reached >= escalation.steps.len() {
+            return None;
+        }
+
+        Some(self.last_break.notified()? + escalation.delay * (reached as u32 + 1))
+    }
+
+    /// Escalates the open reminder if its next step is due at `now`, returns the step reached.
+    pub fn escalate(&mut self, now: Instant) -> Option<EscalationStep> {
+        if self.next_escalation()? > now {
+            return None;
+        }
+
+        let reached = self.last_break.escalations.len();
+        let step = self.escalation.as_ref()?.steps[reached];
+        self.last_break.escalate(step, now);
+
+        Some(step)
+    }
+
+    /// The length of the next break this schedule will send.
+    pub fn next_break_length(&self) -> Duration {
+        match self.pomodoro.as_ref() {
+            Some(pomodoro) => pomodoro.next_break_length(),
+            None => self.break_length,
+        }
+    }
+
+    /// How long was worked at `now` since the last break ended, or since the schedule was last
+    /// restarted.
+    pub fn worked(&self, now: Instant) -> Duration {
+        now.saturating_duration_since(self.last_break.changed())
+    }
+
+    /// The length of a break started at `now`, scaled with the time worked if the schedule
+    /// scales its breaks.
+    pub fn break_length_at(&self, now: Instant) -> Duration {
+        self.scaled_break_length(now)
+            .unwrap_or_else(|| self.next_break_length())
+    }
+
+    /// The length of a break started at `now` scaled with the time worked, `None` if the
+    /// schedule does not scale its breaks. Pomodoro breaks keep their own lengths.
+    fn scaled_break_length(&self, now: Instant) -> Option<Duration> {
+        if self.pomodoro.is_some() {
+            return None;
+        }
+
+        self.break_scaling
+            .as_ref()
+            .and_then(|scaling| scaling.length(self.worked(now)))
+    }
+
+    /// Sets the interval, an adaptive interval adapts from the new one.
+    pub fn set_interval(&mut self, interval: Duration) {
+        self.interval = interval;
+        if let Some(adaptive) = self.adaptive.as_mut() {
+            adaptive.set_base(interval);
+        }
+    }
+
+    /// Adapts the interval to how the break that just ended was taken.
+    pub fn adapt(&mut self, now: Instant) {
+        let Some(adaptive) = self.adaptive.as_mut() else {
+            return;
+        };
+
+        if let Some(adjustment) = adaptive.record(&self.last_break, self.interval, now) {
+            info!(
+                "{} interval adjusted from {} to {} minutes, {}",
+                self.name,
+                adjustment.from.as_secs() / 60,
+                adjustment.to.as_secs() / 60,
+                adjustment.reason
+            );
+            self.interval = adjustment.to;
+        }
+    }
+
+    /// Restarts the interval from `now` unless a reminder is shown, a snoozed break is expired.
+    /// Returns the expired break, if any.
+    pub fn restart(&mut self, now: Instant) -> Option<Break> {
+        match self.last_break.state() {
+            BreakState::Snoozed(_) => {
+                info!("{} break expired as its interval restarted", self.name);
+                // A snoozed break can always expire.
+                let _ = self.last_break.expire(now);
+                return Some(self.last_break.clone());
+            }
+            state if !state.is_outstanding() => self.last_break = Break::scheduled(now),
+            _ => {}
+        }
+
+        None
+    }
+
+    /// Starts a new break at `now` with the activity picked for it, made longer by `debt` to make
+    /// up for missed breaks, and returns the reminder for it. A forced break does not complete
+    /// the pomodoro work period it cuts short.
+    pub fn start_break(
+        &mut self,
+        now: Instant,
+        activity: Option<String>,
+        debt: Duration,
+        forced: bool,
+    ) -> Reminder {
+        let worked = self.scaled_break_length(now).map(|_| self.worked(now));
+        let length = self.break_length_at(now) + debt;
+        self.last_break = Break::new(now, length);
+        self.last_break.debt = debt;
+        self.last_break.worked = worked;
+        self.last_break.activity = activity;
+        self.last_break.routine = self.routine.as_ref().map(BreakRoutine::new);
+        // A new break is always scheduled, so it can always be notified.
+        let _ = self.last_break.notify(now);
+
+        if let Some(pomodoro) = self.pomodoro.as_mut().filter(|_| !forced) {
+            let cycle = pomodoro.complete_cycle(now);
+            info!("{}", cycle.describe(now).trim_start());
+            self.last_break.pomodoro_cycle = Some(cycle.cycle);
+        }
+
+        self.reminder()
+    }
+
+    /// Sends the snoozed break again at `now` and returns the reminder for it, the break keeps
+    /// its id and snooze count.
+    pub fn resume_break(&mut self, now: Instant) -> Result<Reminder, break_reminder::Error> {
+        self.last_break.notify(now)?;
+
+        Ok(self.reminder())
+    }
+
+    /// The reminder for the last break.
+    fn reminder(&self) -> Reminder {
+        let activity = self.last_break.activity.as_ref().unwrap_or(&self.message);
+        let mut message = match self.last_break.pomodoro_cycle {
+            Some(cycle) => format!(
+                "Pomodoro {} complete, take a {} minute break.\n{}",
+                cycle,
+                self.last_break.length.as_secs() / 60,
+                activity
+            ),
+            None => activity.clone(),
+        };
+
+        if let Some(worked) = self.last_break.worked {
+            message.push_str(&format!(
+                "\n\nYou have worked for {}, take a break of {}.",
+                format_length(worked),
+                format_length(self.last_break.length.saturating_sub(self.last_break.debt))
+            ));
+        }
+
+        if !self.last_break.debt.is_zero() {
+            message.push_str(&format!(
+                "\n\nThis break is {} longer to make up for missed breaks.",
+                format_length(self.last_break.debt)
+            ));
+        }
+
+        let snooze_length =
+            (self.last_break.snoozes < self.max_snoozes).then_some(self.snooze_length);
+
+        Reminder {
+            break_id: self.last_break.id,
+            schedule: self.name.clone(),
+            message,
+            length: self.last_break.length,
+            auto_close: self.auto_close,
+            enforce_length: self.enforce_break_length,
+            snooze_length,
+            escalation_steps: self
+                .escalation
+                .as_ref()
+                .map(|escalation| escalation.steps.clone())
+                .unwrap_or_default(),
+            escalation_level: self.last_break.escalation_level.clone(),
+            routine: self.routine.clone(),
+            routine_progress: self
+                .last_break
+                .routine
+                .as_ref()
+                .map(|routine| routine.progress.clone())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Describes the schedule relative to the clock's current time.
+    pub fn describe(&self, clock: &dyn Clock) -> String {
+        let now = clock.now();
+        let when = match self.trigger.as_ref() {
+            Some(trigger) => trigger.describe(),
+            None => format!(
+                "every {} minutes",
+                self.interval_at(clock.local_now()).as_secs() / 60
+            ),
+        };
+        let mut description = format!("{}: {}, {}", self.name, when, self.last_break.describe(now));
+
+        if self.trigger.is_some() || self.alignment.is_some() || self.curve.is_some() {
+            if let Some(due) = self.next_due(clock) {
+                let due_local = calendar::local_at(clock, due);
+                description.push_str(&format!(
+                    "\nNext reminder at {}",
+                    due_local.format("%a %H:%M")
+                ));
+            }
+        }
+
+        if let Some(adaptive) = self.adaptive.as_ref() {
+            description.push_str(&format!("\n{}", adaptive.describe(now)));
+        }
+
+        if let Some(scaling) = self.break_scaling.as_ref() {
+            description.push_str(&format!("\n{}", scaling.describe()));
+        }
+
+        if let Some(curve) = self.curve.as_ref() {
+            description.push_str(&format!("\n{}", curve.describe()));
+        }
+
+        if let Some(alignment) = self.alignment.as_ref() {
+            description.push_str(&format!("\n{}", alignment.describe()));
+        }
+
+        if let Some(pomodoro) = self.pomodoro.as_ref() {
+            description.push_str(&format!("\n{}", pomodoro.describe(now)));
+        }
+
+        description
+    }
+}
@@ -0,0 +1,135 @@
+use std::{fs, io, path::Path, time::Duration};
+
+#[cfg(test)]
+use std::sync::{Arc, Mutex};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+const SCREEN_TIME_PATH: &str = "./screen-time.toml";
+
+/// The active time accumulated over a calendar day.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScreenTime {
+    /// The local date the time was accumulated on.
+    pub date: NaiveDate,
+
+    /// How long the user was active on the date.
+    #[serde(with = "humantime_serde")]
+    pub active: Duration,
+
+    /// How many daily limit notifications have been sent on the date.
+    pub notified: u32,
+}
+
+impl ScreenTime {
+    /// Constructs an empty day.
+    pub fn new(date: NaiveDate) -> Self {
+        Self {
+            date,
+            active: Duration::ZERO,
+            notified: 0,
+        }
+    }
+
+    /// Adds active time on `date`, starting a new day if the date has changed.
+    pub fn add(&mut self, date: NaiveDate, active: Duration) {
+        if date != self.date {
+            *self = Self::new(date);
+        }
+
+        self.active += active;
+    }
+
+    /// Describes the screen time against an optional daily budget.
+    pub fn describe(&self, budget: Option<Duration>) -> String {
+        match budget {
+            Some(budget) => format!(
+                "Screen time today: {} of {}",
+                format_hours(self.active),
+                format_hours(budget)
+            ),
+            None => format!("Screen time today: {}", format_hours(self.active)),
+        }
+    }
+}
+
+/// Formats a duration as hours and minutes.
+pub fn format_hours(duration: Duration) -> String {
+    let minutes = duration.as_secs() / 60;
+    format!("{}h {:02}m", minutes / 60, minutes % 60)
+}
+
+/// Where the screen time is kept between runs, allows persistence to be faked in tests.
+pub trait ScreenTimeStore: Send {
+    /// Loads the last saved screen time, `None` if none has been saved.
+    fn load(&self) -> Result<Option<ScreenTime>, Error>;
+
+    /// Saves the screen time, replacing the last saved one.
+    fn save(&self, screen_time: &ScreenTime) -> Result<(), Error>;
+}
+
+/// Keeps the screen time in `screen-time.toml` next to the config file.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FileStore;
+
+impl ScreenTimeStore for FileStore {
+    fn load(&self) -> Result<Option<ScreenTime>, Error> {
+        let contents = match fs::read_to_string(Path::new(SCREEN_TIME_PATH)) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(Error::Read(e)),
+        };
+
+        Ok(Some(toml::from_str(&contents)?))
+    }
+
+    fn save(&self, screen_time: &ScreenTime) -> Result<(), Error> {
+        let contents = toml::to_string(screen_time)?;
+        fs::write(Path::new(SCREEN_TIME_PATH), contents).map_err(Error::Write)
+    }
+}
+
+/// A store kept in memory, clones share the saved screen time.
+#[cfg(test)]
+#[derive(Clone, Debug, Default)]
+pub struct MemoryStore {
+    saved: Arc<Mutex<Option<ScreenTime>>>,
+}
+
+#[cfg(test)]
+impl MemoryStore {
+    /// The last saved screen time.
+    pub fn saved(&self) -> Option<ScreenTime> {
+        self.saved.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+impl ScreenTimeStore for MemoryStore {
+    fn load(&self) -> Result<Option<ScreenTime>, Error> {
+        Ok(self.saved())
+    }
+
+    fn save(&self, screen_time: &ScreenTime) -> Result<(), Error> {
+        *self.saved.lock().unwrap() = Some(screen_time.clone());
+        Ok(())
+    }
+}
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("Failed to read screen time:\n{0}")]
+    Read(#[source] io::Error),
+
+    #[error("Failed to parse screen time:\n{0}")]
+    Parse(#[from] toml::de::Error),
+
+    #[error("Failed to serialize screen time:\n{0}")]
+    Serialize(#[from] toml::ser::Error),
+
+    #[error("Failed to write screen time:\n{0}")]
+    Write(#[source] io::Error),
+}
@@ -0,0 +1,237 @@
+use std::{fmt, str::FromStr};
+
+use chrono::{Datelike, Days, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::Deserialize;
+use thiserror::Error;
+
+/// How far ahead a cron expression is searched for its next time, long enough to reach a
+/// 29th of February.
+const CRON_SEARCH_DAYS: u64 = 366 * 5;
+
+/// Sends a schedule's reminders at fixed local times instead of every interval.
+#[derive(Clone, Debug)]
+pub struct Trigger {
+    /// The times of day to remind at.
+    pub times: Vec<NaiveTime>,
+
+    /// The cron expression to remind at, if any.
+    pub cron: Option<Cron>,
+}
+
+impl Trigger {
+    /// Constructs a trigger from the times and cron expression, `None` if there are neither.
+    pub fn new(times: &[NaiveTime], cron: Option<&Cron>) -> Option<Self> {
+        if times.is_empty() && cron.is_none() {
+            return None;
+        }
+
+        let mut times = times.to_vec();
+        times.sort();
+
+        Some(Self {
+            times,
+            cron: cron.cloned(),
+        })
+    }
+
+    /// Returns the first time after `after` the trigger fires, `None` if it never fires.
+    pub fn next_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let next_time = [after.date(), after.date() + Days::new(1)]
+            .into_iter()
+            .flat_map(|date| self.times.iter().map(move |time| date.and_time(*time)))
+            .find(|time| *time > after);
+        let next_cron = self.cron.as_ref().and_then(|cron| cron.next_after(after));
+
+        [next_time, next_cron].into_iter().flatten().min()
+    }
+
+    /// Describes when the trigger fires.
+    pub fn describe(&self) -> String {
+        let times: Vec<_> = self
+            .times
+            .iter()
+            .map(|time| time.format("%H:%M").to_string())
+            .collect();
+
+        match (times.is_empty(), self.cron.as_ref()) {
+            (false, Some(cron)) => format!("at {} and on \"{cron}\"", times.join(", ")),
+            (true, Some(cron)) => format!("on \"{cron}\""),
+            _ => format!("at {}", times.join(", ")),
+        }
+    }
+}
+
+/// A five field cron expression of minute, hour, day of month, month and day of week, e.g.
+/// `0 */2 * * 1-5`. Fields are numbers, ranges, `*`, steps and comma separated lists, Sunday is
+/// 0 or 7.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Cron {
+    /// The expression as written.
+    expression: String,
+
+    /// Bit `n` is set if minute `n` matches.
+    minutes: u64,
+
+    /// Bit `n` is set if hour `n` matches.
+    hours: u64,
+
+    /// Bit `n` is set if day `n` of the month matches.
+    days: u64,
+
+    /// Bit `n` is set if month `n` matches.
+    months: u64,
+
+    /// Bit `n` is set if day `n` of the week matches, from Sunday as 0.
+    weekdays: u64,
+
+    /// If the day of month field is restricted, when both day fields are restricted either can
+    /// match.
+    days_restricted: bool,
+
+    /// If the day of week field is restricted.
+    weekdays_restricted: bool,
+}
+
+impl Cron {
+    /// Returns the first minute after `after` that matches the expression, `None` if none does
+    /// within the next few years.
+    pub fn next_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        (0..CRON_SEARCH_DAYS)
+            .filter_map(|offset| after.date().checked_add_days(Days::new(offset)))
+            .filter(|date| self.matches_date(*date))
+            .find_map(|date| {
+                (0..24u32)
+                    .filter(|hour| self.hours & (1 << hour) != 0)
+                    .flat_map(|hour| {
+                        (0..60u32)
+                            .filter(|minute| self.minutes & (1 << minute) != 0)
+                            .filter_map(move |minute| date.and_hms_opt(hour, minute, 0))
+                    })
+                    .find(|time| *time > after)
+            })
+    }
+
+    /// Returns if the day fields match `date`.
+    fn matches_date(&self, date: NaiveDate) -> bool {
+        if self.months & (1 << date.month()) == 0 {
+            return false;
+        }
+
+        let day = self.days & (1 << date.day()) != 0;
+        let weekday = self.weekdays & (1 << date.weekday().num_days_from_sunday()) != 0;
+
+        match (self.days_restricted, self.weekdays_restricted) {
+            (true, true) => day || weekday,
+            _ => day && weekday,
+        }
+    }
+}
+
+impl FromStr for Cron {
+    type Err = Error;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<_> = expression.split_whitespace().collect();
+        let [minutes, hours, days, months, weekdays] = fields[..] else {
+            return Err(Error::FieldCount(expression.to_string()));
+        };
+
+        let field = |field: &str, min, max| {
+            parse_field(field, min, max)
+                .ok_or_else(|| Error::InvalidField(expression.to_string(), field.to_string()))
+        };
+
+        let mut weekday_bits = field(weekdays, 0, 7)?;
+        // Sunday can be written as either 0 or 7.
+        if weekday_bits & (1 << 7) != 0 {
+            weekday_bits |= 1;
+        }
+
+        Ok(Self {
+            expression: expression.to_string(),
+            minutes: field(minutes, 0, 59)?,
+            hours: field(hours, 0, 23)?,
+            days: field(days, 1, 31)?,
+            months: field(months, 1, 12)?,
+            weekdays: weekday_bits,
+            days_restricted: !days.starts_with('*'),
+            weekdays_restricted: !weekdays.starts_with('*'),
+        })
+    }
+}
+
+impl TryFrom<String> for Cron {
+    type Error = Error;
+
+    fn try_from(expression: String) -> Result<Self, Self::Error> {
+        expression.parse()
+    }
+}
+
+impl fmt::Display for Cron {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+/// Parses a comma separated cron field into a bitset of the values between `min` and `max`
+/// that match, `None` if the field is invalid.
+fn parse_field(field: &str, min: u32, max: u32) -> Option<u64> {
+    let mut bits = 0;
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, Some(step.parse::<usize>().ok()?)),
+            None => (part, None),
+        };
+
+        let (start, end) = match range.split_once('-') {
+            _ if range == "*" => (min, max),
+            Some((start, end)) => (start.parse().ok()?, end.parse().ok()?),
+            // A single value with a step runs to the end of the field.
+            None if step.is_some() => (range.parse().ok()?, max),
+            None => {
+                let value = range.parse().ok()?;
+                (value, value)
+            }
+        };
+
+        if start < min || end > max || start > end || step == Some(0) {
+            return None;
+        }
+
+        for value in (start..=end).step_by(step.unwrap_or(1)) {
+            bits |= 1 << value;
+        }
+    }
+
+    Some(bits)
+}
+
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("Cron expression \"{0}\" must have five fields")]
+    FieldCount(String),
+
+    #[error("Cron expression \"{0}\" has an invalid field \"{1}\"")]
+    InvalidField(String, String),
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Config;
+
+    use super::*;
+
+    #[test]
+    fn invalid_cron_is_rejected() {
+        for cron in ["0 */2 * *", "60 * * * *", "0 */0 * * *", "0 9 * * mon"] {
+            assert!(cron.parse::<Cron>().is_err(), "{cron}");
+        }
+
+        let config = "[[schedules]]\nname = \"Walk\"\ncron = \"0 25 * * *\"";
+        assert!(toml::from_str::<Config>(config).is_err());
+    }
+}
@@ -0,0 +1,70 @@
+use chrono::{Datelike, Days, NaiveDateTime, NaiveTime, Weekday};
+use serde::Deserialize;
+
+/// The weekly windows reminders are sent in, outside of them reminders are held back.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(transparent)]
+pub struct WorkingHours {
+    pub windows: Vec<WorkingWindow>,
+}
+
+/// A daily window of working time on the given weekdays, e.g. the morning before a lunch gap.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WorkingWindow {
+    /// The weekdays the window applies to.
+    pub days: Vec<Weekday>,
+
+    /// The local time the window starts.
+    pub start: NaiveTime,
+
+    /// The local time the window ends, must be after `start`.
+    pub end: NaiveTime,
+}
+
+impl WorkingHours {
+    /// Returns the start of the window that contains `local`, `None` outside of working hours.
+    pub fn window_start(&self, local: NaiveDateTime) -> Option<NaiveDateTime> {
+        self.windows
+            .iter()
+            .filter(|window| window.contains(local))
+            .map(|window| local.date().and_time(window.start))
+            .min()
+    }
+
+    /// Returns if `local` is within working hours.
+    pub fn contains(&self, local: NaiveDateTime) -> bool {
+        self.window_start(local).is_some()
+    }
+
+    /// Returns the first window start after `local` within the next week, `None` if no window
+    /// is ever open.
+    pub fn next_start(&self, local: NaiveDateTime) -> Option<NaiveDateTime> {
+        (0..=7)
+            .filter_map(|offset| local.date().checked_add_days(Days::new(offset)))
+            .flat_map(|date| {
+                self.windows
+                    .iter()
+                    .filter(move |window| window.days.contains(&date.weekday()))
+                    .map(move |window| date.and_time(window.start))
+            })
+            .filter(|start| *start > local)
+            .min()
+    }
+
+    /// Returns the first window that ends at or before it starts.
+    pub fn invalid_window(&self) -> Option<&WorkingWindow> {
+        self.windows
+            .iter()
+            .find(|window| window.end <= window.start)
+    }
+}
+
+impl WorkingWindow {
+    /// Returns if `local` is within the window.
+    pub fn contains(&self, local: NaiveDateTime) -> bool {
+        self.days.contains(&local.weekday())
+            && self.start <= local.time()
+            && local.time() < self.end
+    }
+}